@@ -0,0 +1,216 @@
+use crate::report::DayReport;
+use anyhow::{Context, Result};
+use common::report::Outcome;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// What each day's solution last gave for its full input, updated after every run
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Answers {
+    /// Keyed by `YYYY-DD`
+    days: BTreeMap<String, DayRecord>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct DayRecord {
+    /// Of the input the answers passed against, see [`crate::inputs::fingerprint`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub part1: Option<PartRecord>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub part2: Option<PartRecord>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PartRecord {
+    pub answer: String,
+    /// Matched the expected answer, so the star is earned
+    pub passed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub elapsed_ms: Option<f64>,
+}
+
+impl Answers {
+    /// Empty if nothing has been recorded yet
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        };
+
+        toml::from_str(&text).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let text = toml::to_string(self).context("Failed to serialise answers")?;
+        std::fs::write(path, text).with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    pub fn get(&self, year: u32, day: u32) -> Option<&DayRecord> {
+        self.days.get(&key(year, day))
+    }
+
+    pub fn years(&self) -> impl Iterator<Item = u32> + '_ {
+        self.days
+            .keys()
+            .filter_map(|key| key.split_once('-')?.0.parse().ok())
+    }
+
+    /// Years, days and input fingerprints of every fingerprinted day
+    pub fn fingerprints(&self) -> impl Iterator<Item = (u32, u32, &str)> {
+        self.days.iter().filter_map(|(key, record)| {
+            let (year, day) = key.split_once('-')?;
+            Some((
+                year.parse().ok()?,
+                day.parse().ok()?,
+                record.input_hash.as_deref()?,
+            ))
+        })
+    }
+
+    /// Whether the input's fingerprint differs from the one answers last passed against
+    pub fn input_changed(&self, year: u32, day: u32, fingerprint: &str) -> bool {
+        self.get(year, day)
+            .and_then(|record| record.input_hash.as_deref())
+            .is_some_and(|recorded| recorded != fingerprint)
+    }
+
+    /// Takes the full-input cases of the default implementation, returning whether anything
+    /// changed. Timings alone don't count, so rerunning unchanged solutions leaves the file be.
+    /// A case that errors loses its star but keeps the answer it last gave. Passing pins the
+    /// input's fingerprint.
+    pub fn record(&mut self, report: &DayReport) -> bool {
+        let mut changed = false;
+        for case in &report.cases {
+            let case = &case.report;
+            if !case.full_input || case.implementation.is_some() {
+                continue;
+            }
+
+            let record = self.days.entry(key(report.year, report.day)).or_default();
+            let part = match case.part {
+                1 => &mut record.part1,
+                2 => &mut record.part2,
+                _ => continue,
+            };
+
+            let updated = match (case.outcome, &case.answer) {
+                (Outcome::Pass | Outcome::WrongAnswer | Outcome::Unchecked, Some(answer)) => {
+                    Some(PartRecord {
+                        answer: answer.clone(),
+                        passed: case.outcome == Outcome::Pass,
+                        // whole microseconds, to keep float noise out of the file
+                        elapsed_ms: case.elapsed_secs.map(|secs| (secs * 1e6).round() / 1e3),
+                    })
+                }
+                _ => part.clone().map(|part| PartRecord {
+                    passed: false,
+                    ..part
+                }),
+            };
+
+            changed |=
+                part.as_ref().map(PartRecord::result) != updated.as_ref().map(PartRecord::result);
+            *part = updated;
+
+            if case.outcome == Outcome::Pass && report.input_hash.is_some() {
+                changed |= record.input_hash != report.input_hash;
+                record.input_hash.clone_from(&report.input_hash);
+            }
+        }
+
+        self.days.retain(|_, record| {
+            record.part1.is_some() || record.part2.is_some() || record.input_hash.is_some()
+        });
+        changed
+    }
+}
+
+impl PartRecord {
+    fn result(&self) -> (&str, bool) {
+        (&self.answer, self.passed)
+    }
+}
+
+impl DayRecord {
+    pub fn stars(&self) -> usize {
+        [&self.part1, &self.part2]
+            .into_iter()
+            .filter(|part| part.as_ref().is_some_and(|part| part.passed))
+            .count()
+    }
+}
+
+fn key(year: u32, day: u32) -> String {
+    format!("{year}-{day:02}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::CapturedCase;
+    use common::report::CaseReport;
+
+    fn case(part: u8, full_input: bool, outcome: Outcome, answer: Option<&str>) -> CapturedCase {
+        CapturedCase {
+            report: CaseReport {
+                case: 1,
+                part,
+                full_input,
+                implementation: None,
+                outcome,
+                answer: answer.map(str::to_owned),
+                expected: None,
+                message: None,
+                elapsed_secs: Some(0.5),
+            },
+            output: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_record() {
+        let mut answers = Answers::default();
+        let mut report = DayReport {
+            year: 2024,
+            day: 3,
+            cases: vec![
+                case(1, false, Outcome::Pass, Some("161")),
+                case(1, true, Outcome::Pass, Some("170068701")),
+                case(2, true, Outcome::WrongAnswer, Some("12")),
+            ],
+            error: None,
+            source: None,
+            peak_rss_kb: None,
+            input_hash: Some("sha256:1".to_owned()),
+        };
+
+        assert!(answers.record(&report));
+        assert!(!answers.record(&report));
+        let record = answers.get(2024, 3).unwrap();
+        assert_eq!(record.stars(), 1);
+        assert_eq!(record.part1.as_ref().unwrap().answer, "170068701");
+        assert_eq!(record.part1.as_ref().unwrap().elapsed_ms, Some(500.0));
+        report.cases[1].report.elapsed_secs = Some(0.25);
+        assert!(!answers.record(&report));
+
+        report.cases = vec![case(1, true, Outcome::Panic, None)];
+        assert!(answers.record(&report));
+        let part1 = answers.get(2024, 3).unwrap().part1.clone().unwrap();
+        assert!(!part1.passed);
+        assert_eq!(part1.answer, "170068701");
+
+        assert!(!answers.input_changed(2024, 3, "sha256:1"));
+        assert!(answers.input_changed(2024, 3, "sha256:2"));
+        assert!(!answers.input_changed(2024, 4, "sha256:2"));
+
+        let text = toml::to_string(&answers).unwrap();
+        assert!(text.starts_with("[2024-03]\ninput_hash = \"sha256:1\"\n\n[2024-03.part1]\n"));
+        let loaded: Answers = toml::from_str(&text).unwrap();
+        assert_eq!(loaded.years().collect::<Vec<_>>(), [2024]);
+    }
+}
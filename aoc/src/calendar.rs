@@ -0,0 +1,219 @@
+//! When puzzles unlock and how many days each year has
+
+use crate::cli::CalendarArgs;
+use crate::config::Config;
+use crate::NO_YEAR;
+use anyhow::{Context, Result};
+use std::io::{IsTerminal, Write};
+use std::ops::RangeInclusive;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub const FIRST_YEAR: u32 = 2015;
+/// Every day any year can have
+pub const DAYS: RangeInclusive<u32> = 1..=25;
+/// The first year with 12 days rather than 25
+const FIRST_SHORT_YEAR: u32 = 2025;
+
+const DAY_SECS: u64 = 24 * 60 * 60;
+/// Puzzles unlock at midnight US Eastern, which is always EST in December
+const EASTERN_OFFSET_SECS: u64 = 5 * 60 * 60;
+
+/// The days of a year's event
+pub fn days(year: u32) -> RangeInclusive<u32> {
+    if year >= FIRST_SHORT_YEAR {
+        1..=12
+    } else {
+        DAYS
+    }
+}
+
+/// Seconds since the Unix epoch
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs())
+}
+
+/// When a puzzle unlocks, in seconds since the Unix epoch
+pub fn unlock(year: u32, day: u32) -> u64 {
+    days_from_civil(year, 12, day) * DAY_SECS + EASTERN_OFFSET_SECS
+}
+
+pub fn is_unlocked(year: u32, day: u32, now: u64) -> bool {
+    now >= unlock(year, day)
+}
+
+/// The puzzle unlocked today, US Eastern, while an event is running
+pub fn today(now: u64) -> Option<(u32, u32)> {
+    let (year, month, day) = civil_from_days(now.saturating_sub(EASTERN_OFFSET_SECS) / DAY_SECS);
+    (month == 12 && days(year).contains(&day)).then_some((year, day))
+}
+
+/// The next puzzle to unlock
+pub fn next(now: u64) -> (u32, u32) {
+    let (year, month, day) = civil_from_days(now.saturating_sub(EASTERN_OFFSET_SECS) / DAY_SECS);
+    match month {
+        12 if day < *days(year).end() => (year, day + 1),
+        12 => (year + 1, 1),
+        _ => (year, 1),
+    }
+}
+
+/// e.g. "2025-12-05 00:00 EST"
+pub fn unlock_time(year: u32, day: u32) -> String {
+    format!("{year}-12-{day:02} 00:00 EST")
+}
+
+/// e.g. "3d 4h 5m 6s", without leading zero units
+pub fn countdown(secs: u64) -> String {
+    let units = [
+        (secs / DAY_SECS, "d"),
+        (secs / 3600 % 24, "h"),
+        (secs / 60 % 60, "m"),
+        (secs % 60, "s"),
+    ];
+    let first = units.iter().position(|&(n, _)| n > 0).unwrap_or(3);
+    units[first..]
+        .iter()
+        .map(|(n, unit)| format!("{n}{unit}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Shows when a puzzle unlocks, by default the next one, failing if it hasn't yet unless told to
+/// wait for it
+pub fn run(config: &Config, args: CalendarArgs) -> Result<()> {
+    let now = now();
+    if let Some((year, day)) = today(now) {
+        println!("today's puzzle is {year} day {day}");
+    }
+
+    let (year, day) = match (args.year, args.day) {
+        (year, Some(day)) => (year.or(config.year).context(NO_YEAR)?, day),
+        (Some(year), None) => match days(year).find(|&day| !is_unlocked(year, day, now)) {
+            Some(day) => (year, day),
+            None => {
+                println!("all {} days of {year} are unlocked", days(year).count());
+                return Ok(());
+            }
+        },
+        (None, None) => next(now),
+    };
+    check_day(year, day)?;
+
+    if is_unlocked(year, day, now) {
+        println!("{year} day {day} unlocked at {}", unlock_time(year, day));
+        return Ok(());
+    }
+    let left = unlock(year, day) - now;
+    let message = format!(
+        "{year} day {day} unlocks in {} at {}",
+        countdown(left),
+        unlock_time(year, day)
+    );
+    if !args.wait {
+        anyhow::ensure!(args.day.is_none(), message);
+        println!("{message}");
+        return Ok(());
+    }
+
+    let interactive = std::io::stdout().is_terminal();
+    if !interactive {
+        println!("{message}");
+    }
+    loop {
+        let now = self::now();
+        if is_unlocked(year, day, now) {
+            break;
+        }
+        if interactive {
+            print!(
+                "\r{year} day {day} unlocks in {}\x1b[K",
+                countdown(unlock(year, day) - now)
+            );
+            std::io::stdout().flush().ok();
+        }
+        std::thread::sleep(Duration::from_secs(1));
+    }
+    if interactive {
+        println!();
+    }
+    println!("{year} day {day} is unlocked");
+    Ok(())
+}
+
+/// Fails for days past the end of the year's event
+pub fn check_day(year: u32, day: u32) -> Result<()> {
+    let days = days(year);
+    anyhow::ensure!(
+        days.contains(&day),
+        "{year} has {} days, there's no day {day}",
+        days.count()
+    );
+    Ok(())
+}
+
+/// Days since the Unix epoch of a date in the proleptic Gregorian calendar
+fn days_from_civil(year: u32, month: u32, day: u32) -> u64 {
+    let year = if month <= 2 { year - 1 } else { year } as u64;
+    let era = year / 400;
+    let year_of_era = year % 400;
+    let month = month as u64;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day as u64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// The date of a number of days since the Unix epoch
+fn civil_from_days(days: u64) -> (u32, u32, u32) {
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+    (year as u32, month as u32, day as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calendar() {
+        // 2024-12-01T05:00:00Z
+        let unlock_2024_1 = 1_733_029_200;
+        assert_eq!(unlock(2024, 1), unlock_2024_1);
+        assert_eq!(civil_from_days(unlock_2024_1 / DAY_SECS), (2024, 12, 1));
+        assert_eq!(civil_from_days(days_from_civil(2000, 2, 29)), (2000, 2, 29));
+
+        assert!(!is_unlocked(2024, 1, unlock_2024_1 - 1));
+        assert!(is_unlocked(2024, 1, unlock_2024_1));
+        assert_eq!(today(unlock_2024_1 - 1), None);
+        assert_eq!(today(unlock_2024_1), Some((2024, 1)));
+        assert_eq!(today(unlock(2024, 25) + DAY_SECS - 1), Some((2024, 25)));
+        assert_eq!(today(unlock(2024, 25) + DAY_SECS), None);
+        assert_eq!(today(unlock(2025, 12) + DAY_SECS), None);
+
+        assert_eq!(next(unlock_2024_1 - 1), (2024, 1));
+        assert_eq!(next(unlock_2024_1), (2024, 2));
+        assert_eq!(next(unlock(2025, 12)), (2026, 1));
+        assert_eq!(next(unlock(2024, 1) - 40 * DAY_SECS), (2024, 1));
+
+        assert_eq!(days(2024), 1..=25);
+        assert_eq!(days(2025), 1..=12);
+        assert!(check_day(2025, 13).is_err());
+
+        assert_eq!(countdown(3 * DAY_SECS + 4 * 3600 + 6), "3d 4h 0m 6s");
+        assert_eq!(countdown(65), "1m 5s");
+        assert_eq!(countdown(0), "0s");
+    }
+}
@@ -0,0 +1,171 @@
+//! Edits the case list in a solution's `solution!` invocation
+
+use anyhow::{Context, Result};
+use common::SolutionResult;
+use std::path::Path;
+
+/// Rust source for an answer typed or copied as text, a number if it is one
+pub fn answer_literal(answer: &str) -> String {
+    match answer.parse::<i128>() {
+        Ok(n) => SolutionResult::from(n).literal(),
+        Err(_) => SolutionResult::from(answer).literal(),
+    }
+}
+
+/// Adds `example_partN(answer, CONST)` to the end of the case list, with the input as a new
+/// const after the `solution!` invocation. Returns the const's name.
+pub fn insert_example(
+    source: &Path,
+    prefix: &str,
+    part: u8,
+    answer_literal: &str,
+    input: &str,
+) -> Result<String> {
+    let text = std::fs::read_to_string(source)
+        .with_context(|| format!("Failed to read {}", source.display()))?;
+    let (text, name) =
+        insert_example_in(&text, prefix, part, answer_literal, input).with_context(|| {
+            format!(
+                "Failed to find the `solution!` case list in {}",
+                source.display()
+            )
+        })?;
+    std::fs::write(source, text)
+        .with_context(|| format!("Failed to write {}", source.display()))?;
+    Ok(name)
+}
+
+fn insert_example_in(
+    text: &str,
+    prefix: &str,
+    part: u8,
+    answer_literal: &str,
+    input: &str,
+) -> Option<(String, String)> {
+    let name = (1..)
+        .map(|n| format!("{prefix}_{n}"))
+        .find(|name| !text.contains(&format!("const {name}:")))?;
+
+    let invocation = text.find("solution!(")?;
+    let list_start = invocation + text[invocation..].find('[')?;
+    let list_end = closing_bracket(text, list_start)?;
+    let statement_end = list_end + text[list_end..].find(");")? + 2;
+
+    let before_close = text[..list_end].trim_end();
+    let comma = if before_close.ends_with([',', '[']) {
+        ""
+    } else {
+        ","
+    };
+    let indent = before_close
+        .lines()
+        .last()
+        .map(|line| &line[..line.len() - line.trim_start().len()])
+        .filter(|indent| !indent.is_empty())
+        .unwrap_or("        ");
+    let close_indent = text[..list_end]
+        .rsplit('\n')
+        .next()
+        .filter(|line| line.trim().is_empty())
+        .unwrap_or_default();
+
+    let mut out = String::with_capacity(text.len() + input.len() + 100);
+    out.push_str(before_close);
+    out.push_str(comma);
+    out.push_str(&format!(
+        "\n{indent}example_part{part}({answer_literal}, {name}),\n{close_indent}"
+    ));
+    out.push_str(&text[list_end..statement_end]);
+    out.push_str(&format!(
+        "\n\nconst {name}: &str = \"{}\";",
+        input.replace('\\', "\\\\").replace('"', "\\\"")
+    ));
+    out.push_str(&text[statement_end..]);
+    Some((out, name))
+}
+
+/// Index of the `]` closing the `[` at `open`, skipping string literals
+fn closing_bracket(text: &str, open: usize) -> Option<usize> {
+    let bytes = text.as_bytes();
+    let mut depth = 0;
+    let mut i = open;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'[' => depth += 1,
+            b']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            b'r' if bytes[i + 1..].starts_with(b"#\"") || bytes[i + 1..].starts_with(b"\"") => {
+                let hashes = bytes[i + 1..].iter().take_while(|&&b| b == b'#').count();
+                let close = format!("\"{}", "#".repeat(hashes));
+                let body = i + 2 + hashes;
+                i = body + text[body..].find(&close)? + close.len();
+                continue;
+            }
+            b'"' => {
+                i += 1;
+                while bytes.get(i)? != &b'"' {
+                    i += if bytes[i] == b'\\' { 2 } else { 1 };
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_example() {
+        let source = r#"solution!(
+    Day13_2024,
+    [
+        solution_part1(Some(36758)),
+        example_part1(480, "a ] \" b"),
+        example_part2(5, r"]"),
+    ]
+);
+
+const PART1_EXAMPLE: &str = "x";
+"#;
+
+        let (text, name) = insert_example_in(source, "FUZZ_EXAMPLE", 1, "0", "p \"q\"\nr").unwrap();
+        assert_eq!(name, "FUZZ_EXAMPLE_1");
+        assert_eq!(
+            text,
+            r#"solution!(
+    Day13_2024,
+    [
+        solution_part1(Some(36758)),
+        example_part1(480, "a ] \" b"),
+        example_part2(5, r"]"),
+        example_part1(0, FUZZ_EXAMPLE_1),
+    ]
+);
+
+const FUZZ_EXAMPLE_1: &str = "p \"q\"
+r";
+
+const PART1_EXAMPLE: &str = "x";
+"#
+        );
+
+        let (text, name) = insert_example_in(&text, "FUZZ_EXAMPLE", 2, "1", "s").unwrap();
+        assert_eq!(name, "FUZZ_EXAMPLE_2");
+        assert!(text.contains("        example_part2(1, FUZZ_EXAMPLE_2),\n    ]\n);"));
+
+        let (text, _) =
+            insert_example_in("solution!(Day, [solution_part1(None)]);", "E", 1, "2", "t").unwrap();
+        assert_eq!(
+            text,
+            "solution!(Day, [solution_part1(None),\n        example_part1(2, E_1),\n]);\n\nconst E_1: &str = \"t\";"
+        );
+    }
+}
@@ -0,0 +1,445 @@
+use crate::calendar::{self, DAYS, FIRST_YEAR};
+use crate::config::{Backend, ColourMode, Config, OutputFormat, Profile};
+use crate::external;
+use crate::viz::VizOutput;
+use anyhow::Context;
+use clap::{Args, Parser, Subcommand};
+use common::PartNumber;
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+
+/// Builds and runs Advent of Code solutions against their cases
+#[derive(Debug, Parser)]
+#[command(name = "aoc", args_conflicts_with_subcommands = true)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<CliCommand>,
+
+    /// Without a subcommand, the flags of `run`
+    #[command(flatten)]
+    pub run: RunArgs,
+
+    #[command(flatten)]
+    pub overrides: ConfigOverrides,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum CliCommand {
+    /// Run solutions against their cases (the default)
+    Run(RunArgs),
+    /// Time each implementation on each case
+    Bench(RunArgs),
+    /// List the solutions in the workspace
+    List {
+        /// Years to list, all if not set
+        #[arg(long, value_parser = Selection::parse_years)]
+        year: Option<Selection>,
+    },
+    /// Create a solution crate from the template
+    New(DayArgs),
+    /// Show where a day's solution, input and build live
+    Info(DayArgs),
+    /// Show the effective config and where it came from
+    Config,
+    /// Show which puzzles have stars, from the answers recorded by runs
+    Stars(StarsArgs),
+    /// Compare a day's fuzz target implementations on random inputs
+    Fuzz(FuzzArgs),
+    /// Solve pasted inputs with a day's solution, saving them as example cases
+    Repl(ReplArgs),
+    /// Propose example cases from a saved puzzle page
+    Examples(ExamplesArgs),
+    /// Check inputs haven't changed since their answers were recorded
+    VerifyInputs {
+        /// Years to check [default: all]
+        #[arg(long, value_parser = Selection::parse_years)]
+        year: Option<Selection>,
+    },
+    /// Show when puzzles unlock, failing for a day that hasn't unlocked yet
+    Calendar(CalendarArgs),
+    /// Show a private leaderboard's standings and solve times
+    Leaderboard(LeaderboardArgs),
+    /// Encrypt inputs for committing, or decrypt them
+    Inputs {
+        #[command(subcommand)]
+        command: InputsCommand,
+    },
+}
+
+/// Inputs are kept out of git, but can be committed as `YYYY-DD.enc`, encrypted with a secret from
+/// `AOC_INPUT_KEY` or the `input_key` file. Runs decrypt them when there's no plain input.
+#[derive(Debug, Subcommand)]
+pub enum InputsCommand {
+    /// Write a `.enc` beside each plain input
+    Encrypt {
+        /// Years to encrypt [default: all]
+        #[arg(long, value_parser = Selection::parse_years)]
+        year: Option<Selection>,
+    },
+    /// Write out each `.enc` input as a plain one
+    Decrypt {
+        /// Years to decrypt [default: all]
+        #[arg(long, value_parser = Selection::parse_years)]
+        year: Option<Selection>,
+        /// Overwrite plain inputs that differ
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct RunArgs {
+    /// Years to run, e.g. `2024`, `2015,2024`, `2015-2016`, or `all` for every solution
+    /// [default: `year` from aoc.toml]
+    #[arg(long, value_parser = Years::parse)]
+    pub year: Option<Years>,
+    /// Days to run, e.g. `5`, `1-12`, `1..12` or `1,3,5` [default: all]
+    #[arg(long, value_parser = Selection::parse_days)]
+    pub day: Option<Selection>,
+    /// Parts to run, `1`, `2` or `1,2` [default: both]
+    #[arg(long, value_parser = Selection::parse_parts, conflicts_with_all = ["part1", "part2"])]
+    pub part: Option<Selection>,
+    #[arg(long = "1", hide = true, conflicts_with = "part2")]
+    pub part1: bool,
+    #[arg(long = "2", hide = true)]
+    pub part2: bool,
+    /// Only run this case, 1-based
+    #[arg(long)]
+    pub case: Option<u32>,
+    /// Only run full inputs, skipping examples
+    #[arg(long)]
+    pub only_solutions: bool,
+    /// Implementation name, or `all` to cross-check every implementation
+    #[arg(long = "impl")]
+    pub implementation: Option<String>,
+    /// Time each implementation on each case, as `bench` does
+    #[arg(long)]
+    pub bench: bool,
+    /// Time each day's full input against the solution as of this git revision, e.g. `HEAD`,
+    /// built in a separate worktree
+    #[arg(long, value_name = "REV")]
+    pub compare: Option<String>,
+    /// Record frames and play them in the terminal (`term`), or export to a .gif or .png path
+    #[arg(long, value_parser = VizOutput::parse)]
+    pub viz: Option<VizOutput>,
+    /// Playback speed for --viz
+    #[arg(long, default_value_t = 10)]
+    pub fps: u32,
+    /// Keep only every nth recorded frame
+    #[arg(long, default_value_t = 1)]
+    pub viz_every: u32,
+    /// Also write a self-contained HTML report of every case to this path
+    #[arg(long)]
+    pub html: Option<PathBuf>,
+    /// Hide full-input answers in the HTML report
+    #[arg(long, requires = "html")]
+    pub redact: bool,
+    /// Where to look up solutions run as external programs
+    #[arg(long, default_value = external::DEFAULT_MANIFEST)]
+    pub manifest: PathBuf,
+    /// Run as a worker of a parallel run, writing the day's results here
+    #[arg(long, hide = true)]
+    pub worker: Option<PathBuf>,
+    /// Solutions were already built by the parallel run
+    #[arg(long, hide = true)]
+    pub no_build: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct DayArgs {
+    /// [default: `year` from aoc.toml]
+    #[arg(long, value_parser = clap::value_parser!(u32).range(FIRST_YEAR as i64..))]
+    pub year: Option<u32>,
+    /// [default: today's puzzle, while an event is running]
+    #[arg(long, value_parser = clap::value_parser!(u32).range(*DAYS.start() as i64..=*DAYS.end() as i64))]
+    pub day: Option<u32>,
+}
+
+impl DayArgs {
+    /// The year and day, checked against the year's calendar
+    pub fn resolve(&self, config: &Config) -> anyhow::Result<(u32, u32)> {
+        let (year, day) = match (self.year, self.day) {
+            (year, Some(day)) => (year.or(config.year).context(crate::NO_YEAR)?, day),
+            (year, None) => calendar::today(calendar::now())
+                .filter(|&(today, _)| year.is_none_or(|year| year == today))
+                .context("--day is required, there's no puzzle today")?,
+        };
+        calendar::check_day(year, day)?;
+        Ok((year, day))
+    }
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct CalendarArgs {
+    /// [default: `year` from aoc.toml with --day, otherwise the next puzzle's]
+    #[arg(long, value_parser = clap::value_parser!(u32).range(FIRST_YEAR as i64..))]
+    pub year: Option<u32>,
+    /// Day to check [default: the year's next to unlock]
+    #[arg(long, value_parser = clap::value_parser!(u32).range(*DAYS.start() as i64..=*DAYS.end() as i64))]
+    pub day: Option<u32>,
+    /// Count down until it unlocks rather than failing
+    #[arg(long)]
+    pub wait: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct LeaderboardArgs {
+    /// Year to fetch [default: `year` from aoc.toml]
+    #[arg(long, value_parser = clap::value_parser!(u32).range(FIRST_YEAR as i64..))]
+    pub year: Option<u32>,
+    /// Only show solve times for this day
+    #[arg(long, value_parser = clap::value_parser!(u32).range(*DAYS.start() as i64..=*DAYS.end() as i64))]
+    pub day: Option<u32>,
+    /// Read the leaderboard's JSON from this file rather than fetching it
+    #[arg(long, conflicts_with = "year")]
+    pub from: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct StarsArgs {
+    /// Years to show [default: every year with a solution or answer]
+    #[arg(long, value_parser = Selection::parse_years)]
+    pub year: Option<Selection>,
+    /// Also write a progress table into this file, between `<!-- aoc-stars:start -->` and
+    /// `<!-- aoc-stars:end -->`
+    #[arg(long)]
+    pub markdown: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct ReplArgs {
+    #[command(flatten)]
+    pub day: DayArgs,
+    /// Implementation to start with [default: `Solution::solve`]
+    #[arg(long = "impl")]
+    pub implementation: Option<String>,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct ExamplesArgs {
+    #[command(flatten)]
+    pub day: DayArgs,
+    /// The puzzle's page, as saved from the browser
+    #[arg(long)]
+    pub from: PathBuf,
+    /// Add the proposed cases to the solution's case list rather than only printing them
+    #[arg(long)]
+    pub insert: bool,
+    /// Proposals to insert, by number, e.g. `1,3` [default: all]
+    #[arg(long, value_parser = Selection::parse_proposals, requires = "insert")]
+    pub pick: Option<Selection>,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct FuzzArgs {
+    #[command(flatten)]
+    pub day: DayArgs,
+    /// Only fuzz targets for this part
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    pub part: Option<u8>,
+    /// Inputs to try per target
+    #[arg(long, default_value_t = 1000)]
+    pub iterations: u32,
+    /// To reproduce a run [default: random]
+    #[arg(long)]
+    pub seed: Option<u64>,
+    /// Don't add shrunk failing inputs to the solution's example cases
+    #[arg(long)]
+    pub no_save: bool,
+}
+
+/// Flags that take precedence over aoc.toml, for any command
+#[derive(Debug, Clone, Args)]
+pub struct ConfigOverrides {
+    /// Directory of `YYYY-DD` input files
+    #[arg(long, global = true)]
+    pub inputs: Option<PathBuf>,
+    /// Profile solutions are built with
+    #[arg(long, global = true, value_enum)]
+    pub profile: Option<Profile>,
+    /// Shorthand for `--profile release`
+    #[arg(long, global = true, conflicts_with = "profile")]
+    pub release: bool,
+    /// How Rust solutions are run
+    #[arg(long, global = true, value_enum)]
+    pub backend: Option<Backend>,
+    /// Per case, in seconds
+    #[arg(long, global = true)]
+    pub timeout: Option<u64>,
+    /// Days to run at once, each in its own process, or 0 for one per CPU. Benchmarks always
+    /// run one at a time.
+    #[arg(long, short, global = true)]
+    pub jobs: Option<usize>,
+    /// How results are reported
+    #[arg(long, global = true, value_enum)]
+    pub format: Option<OutputFormat>,
+    /// When to colour output
+    #[arg(long, global = true, value_enum, alias = "color")]
+    pub colour: Option<ColourMode>,
+}
+
+impl ConfigOverrides {
+    pub fn apply(&self, config: &mut Config) {
+        if let Some(inputs) = &self.inputs {
+            config.inputs = inputs.clone();
+        }
+        if self.release {
+            config.profile = Profile::Release;
+        }
+        config.profile = self.profile.unwrap_or(config.profile);
+        config.backend = self.backend.unwrap_or(config.backend);
+        config.timeout = self.timeout.or(config.timeout);
+        config.jobs = self.jobs.unwrap_or(config.jobs);
+        config.format = self.format.unwrap_or(config.format);
+        config.colour = self.colour.unwrap_or(config.colour);
+    }
+}
+
+impl RunArgs {
+    /// `None` for both parts
+    pub fn part_filter(&self) -> Option<PartNumber> {
+        let parts = match &self.part {
+            Some(Selection(parts)) => parts.as_slice(),
+            None if self.part1 => &[1],
+            None if self.part2 => &[2],
+            None => &[1, 2],
+        };
+
+        match parts {
+            [1] => Some(PartNumber::Part1),
+            [2] => Some(PartNumber::Part2),
+            _ => None,
+        }
+    }
+}
+
+/// `--year` of a run, which can also be every year with a solution
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Years {
+    All,
+    Only(Selection),
+}
+
+impl Years {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        if s.trim() == "all" {
+            return Ok(Self::All);
+        }
+        Selection::parse_years(s).map(Self::Only)
+    }
+}
+
+/// Numbers from list and range syntax, sorted and deduplicated. Ranges are inclusive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Selection(pub Vec<u32>);
+
+impl Selection {
+    /// `0` is every day, as before ranges were supported. Days are checked against each year's
+    /// calendar once the years are known.
+    pub fn parse_days(s: &str) -> Result<Self, String> {
+        if s.trim() == "0" {
+            return Ok(Self(DAYS.collect()));
+        }
+        Self::parse(s, DAYS, "day")
+    }
+
+    pub fn parse_years(s: &str) -> Result<Self, String> {
+        Self::parse(s, FIRST_YEAR..=u32::MAX, "year")
+    }
+
+    pub fn parse_parts(s: &str) -> Result<Self, String> {
+        Self::parse(s, 1..=2, "part")
+    }
+
+    pub fn parse_proposals(s: &str) -> Result<Self, String> {
+        Self::parse(s, 1..=u32::MAX, "proposal")
+    }
+
+    fn parse(s: &str, bounds: RangeInclusive<u32>, what: &str) -> Result<Self, String> {
+        let number = |n: &str| {
+            let n = n.trim();
+            let n = n
+                .parse::<u32>()
+                .map_err(|_| format!("{n:?} is not a valid {what}"))?;
+            if bounds.contains(&n) {
+                Ok(n)
+            } else if *bounds.end() == u32::MAX {
+                Err(format!("{what} {n} is before {}", bounds.start()))
+            } else {
+                Err(format!(
+                    "{what} {n} is not between {} and {}",
+                    bounds.start(),
+                    bounds.end()
+                ))
+            }
+        };
+
+        let mut selected = Vec::new();
+        for item in s.split(',') {
+            let range = ["..=", "..", "-"]
+                .iter()
+                .find_map(|sep| item.split_once(sep));
+            match range {
+                Some((start, end)) => {
+                    let (start, end) = (number(start)?, number(end)?);
+                    if start > end {
+                        return Err(format!("{item:?} is an empty range"));
+                    }
+                    selected.extend(start..=end);
+                }
+                None => selected.push(number(item)?),
+            }
+        }
+
+        selected.sort_unstable();
+        selected.dedup();
+        Ok(Self(selected))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selection() {
+        assert_eq!(Selection::parse_days("5"), Ok(Selection(vec![5])));
+        assert_eq!(Selection::parse_days("0").unwrap().0.len(), 25);
+        assert_eq!(
+            Selection::parse_days("1-3,7,2..4,10..=11"),
+            Ok(Selection(vec![1, 2, 3, 4, 7, 10, 11]))
+        );
+        assert_eq!(
+            Selection::parse_years("2015,2024"),
+            Ok(Selection(vec![2015, 2024]))
+        );
+
+        assert!(Selection::parse_days("26").is_err());
+        assert!(Selection::parse_days("5-3").is_err());
+        assert!(Selection::parse_days("x").is_err());
+        assert!(Selection::parse_years("2014").is_err());
+    }
+
+    #[test]
+    fn test_legacy_flags() {
+        let cli = Cli::try_parse_from(["aoc", "--year", "2024", "--day", "13", "--2", "--bench"])
+            .unwrap();
+        assert!(cli.command.is_none());
+        assert_eq!(cli.run.year, Some(Years::Only(Selection(vec![2024]))));
+        assert_eq!(cli.run.part_filter(), Some(PartNumber::Part2));
+        assert!(cli.run.bench);
+
+        let cli = Cli::try_parse_from(["aoc", "run", "--day", "1-3", "--part", "1,2"]).unwrap();
+        let Some(CliCommand::Run(run)) = cli.command else {
+            panic!("expected run");
+        };
+        assert_eq!(run.day, Some(Selection(vec![1, 2, 3])));
+        assert_eq!(run.part_filter(), None);
+
+        let cli = Cli::try_parse_from(["aoc", "--year", "all", "--day", "1-12"]).unwrap();
+        assert_eq!(cli.run.year, Some(Years::All));
+
+        assert!(Cli::try_parse_from(["aoc", "--part", "3"]).is_err());
+        assert!(Cli::try_parse_from(["aoc", "config", "--release"]).is_ok());
+    }
+}
@@ -0,0 +1,259 @@
+//! `aoc bench --compare`: times solutions against themselves as of another git revision
+
+use crate::cli::RunArgs;
+use crate::config::Config;
+use crate::discover;
+use crate::external::Manifest;
+use crate::{
+    cargo_build, ensure_solutions_built, inputs, library_in, library_path, repl, selected_days,
+    target_dir,
+};
+use anyhow::{Context, Result};
+use common::bench::{self, Comparison};
+use common::itertools::Itertools;
+use common::{strip_ansi, PartNumber};
+use libloading::Library;
+use log::{info, warn};
+use owo_colors::OwoColorize;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+/// A checkout of the revision, removed once the comparison is done. Its builds go to a target
+/// directory shared by every revision, so comparing against one again is quick.
+struct Worktree {
+    root: PathBuf,
+    path: PathBuf,
+}
+
+impl Worktree {
+    fn add(root: &Path, commit: &str, dir: &Path) -> Result<Self> {
+        let path = dir.join(&commit[..12]);
+        if path.exists() {
+            git(
+                root,
+                &["worktree", "remove", "--force", &path.to_string_lossy()],
+            )
+            .ok();
+            std::fs::remove_dir_all(&path).ok();
+        }
+        git(
+            root,
+            &[
+                "worktree",
+                "add",
+                "--detach",
+                &path.to_string_lossy(),
+                commit,
+            ],
+        )?;
+        let worktree = Self {
+            root: root.to_owned(),
+            path,
+        };
+        // the lock file isn't committed, and the same dependencies keep the comparison fair
+        if root.join("Cargo.lock").exists() {
+            std::fs::copy(root.join("Cargo.lock"), worktree.path.join("Cargo.lock"))
+                .context("Failed to copy Cargo.lock into the worktree")?;
+        }
+        Ok(worktree)
+    }
+}
+
+impl Drop for Worktree {
+    fn drop(&mut self) {
+        let path = self.path.to_string_lossy();
+        if let Err(e) = git(&self.root, &["worktree", "remove", "--force", &path]) {
+            warn!("{e:#}");
+        }
+    }
+}
+
+/// Benchmarks each selected day's solution as built now against the build at `rev`, on the
+/// full input, running the two alternately
+pub fn run(root: &Path, run: &RunArgs, config: &Config, rev: &str) -> Result<()> {
+    anyhow::ensure!(
+        run.implementation.as_deref() != Some("all"),
+        "--compare times one implementation, not all of them"
+    );
+    let manifest = Manifest::load(&root.join(&run.manifest))?;
+    let days = selected_days(root, run, config, manifest.as_ref())?
+        .into_iter()
+        .filter(|&(year, day)| {
+            let rust = manifest
+                .as_ref()
+                .is_none_or(|m| m.find(year, day).is_none())
+                && discover::crate_dir(root, year, day).exists();
+            if !rust {
+                info!("skipping {year} day {day}, only Rust solutions can be compared");
+            }
+            rust
+        })
+        .collect_vec();
+    anyhow::ensure!(!days.is_empty(), "no Rust solutions to compare");
+
+    let commit = git(
+        root,
+        &["rev-parse", "--verify", &format!("{rev}^{{commit}}")],
+    )
+    .with_context(|| format!("{rev:?} is not a revision"))?;
+    let compare_dir = target_dir()?.join("compare");
+    let worktree = Worktree::add(root, &commit, &compare_dir)?;
+    let baseline_target = compare_dir.join("target");
+
+    let (baseline_days, missing): (Vec<_>, Vec<_>) = days
+        .iter()
+        .partition(|&&(year, day)| discover::crate_dir(&worktree.path, year, day).exists());
+    for (year, day) in missing {
+        warn!("skipping {year} day {day}, it has no solution at {rev}");
+    }
+    anyhow::ensure!(
+        !baseline_days.is_empty(),
+        "no solutions to compare at {rev}"
+    );
+
+    info!("building {rev} in {}", worktree.path.display());
+    cargo_build(
+        &worktree.path,
+        Some(&baseline_target),
+        None,
+        config,
+        &baseline_days,
+    )
+    .with_context(|| format!("Failed to build {rev}"))?;
+    ensure_solutions_built(config, &baseline_days).context("Failed to build solutions")?;
+
+    let colour = config.colour_enabled();
+    let say = |text: String| println!("{}", if colour { text } else { strip_ansi(&text) });
+    let implementation = run.implementation.as_deref().unwrap_or_default();
+    let parts = match run.part_filter() {
+        Some(part) => vec![part],
+        None => vec![PartNumber::Part1, PartNumber::Part2],
+    };
+    for (year, day) in baseline_days {
+        let input = match inputs::read(config, year, day) {
+            Ok(input) => input,
+            Err(e) => {
+                say(format!("{year}-{day:02}  {}", format!("{e:#}").red()));
+                continue;
+            }
+        };
+        let input = input.as_str();
+        let baseline = unsafe { Library::new(library_in(&baseline_target, config, year, day)) }
+            .with_context(|| format!("Failed to load the {rev} build"))?;
+        repl::check_abi(&baseline)
+            .with_context(|| format!("{rev} predates --compare support, or has changed it"))?;
+        let current = unsafe { Library::new(library_path(config, year, day)?) }
+            .context("Failed to load solution library")?;
+
+        for &part in &parts {
+            let label = format!("{year}-{day:02} part {}", part as u8);
+            let solve = |library: &Library, build: &str| -> Result<(String, Duration)> {
+                let report = repl::solve(library, input, part, implementation)
+                    .with_context(|| format!("Failed to solve with the {build} build"))?;
+                let answer = report.answer.with_context(|| {
+                    format!(
+                        "{build}: {}",
+                        report.message.unwrap_or_else(|| "no answer".to_owned())
+                    )
+                })?;
+                Ok((answer, Duration::from_secs_f64(report.elapsed_secs)))
+            };
+            let answer = |library, build| solve(library, build).map(|(answer, _)| answer);
+            let (before, after) = match (answer(&baseline, rev), answer(&current, "current")) {
+                (Ok(before), Ok(after)) => (before, after),
+                (Err(e), _) | (_, Err(e)) => {
+                    say(format!("{label}  {}", format!("{e:#}").red()));
+                    continue;
+                }
+            };
+            if before != after {
+                warn!("{label} answers differ, {before} at {rev} and {after} now");
+            }
+
+            // a solve that fails part way through sinks the comparison, rather than timing as zero
+            let time = |library, build| move || solve(library, build).map(|(_, elapsed)| elapsed);
+            match bench::compare(time(&baseline, rev), time(&current, "current")) {
+                Ok(comparison) => say(format!("{label}  {}", describe(&comparison, rev))),
+                Err(e) => say(format!("{label}  {}", format!("{e:#}").red())),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// e.g. "main 1.20ms  now 600.00µs  2.00× faster (1.90×–2.10× speedup, 95% CI of 40 runs)"
+fn describe(comparison: &Comparison, rev: &str) -> String {
+    let Comparison {
+        runs,
+        baseline,
+        candidate,
+        speedup,
+        low,
+        high,
+    } = *comparison;
+    let verdict = if !comparison.is_significant() {
+        "no significant change".bright_black().to_string()
+    } else if speedup > 1.0 {
+        format!("{speedup:.2}× faster").green().bold().to_string()
+    } else {
+        format!("{:.2}× slower", 1.0 / speedup)
+            .red()
+            .bold()
+            .to_string()
+    };
+    format!(
+        "{rev} {:.2?}  now {:.2?}  {verdict} ({low:.2}×–{high:.2}× speedup, 95% CI of {runs} runs)",
+        baseline.median, candidate.median
+    )
+}
+
+/// Runs git in `dir`, returning its trimmed stdout
+fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .context("Failed to run git")?;
+    anyhow::ensure!(
+        output.status.success(),
+        "git {} failed: {}",
+        args.join(" "),
+        String::from_utf8_lossy(&output.stderr).trim()
+    );
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::bench::BenchStats;
+
+    #[test]
+    fn test_describe() {
+        let stats = |ms| BenchStats {
+            runs: 10,
+            min: Duration::from_millis(ms),
+            median: Duration::from_millis(ms),
+            mean: Duration::from_millis(ms),
+        };
+        let comparison = |speedup, low, high| Comparison {
+            runs: 10,
+            baseline: stats(4),
+            candidate: stats(2),
+            speedup,
+            low,
+            high,
+        };
+        assert_eq!(
+            strip_ansi(&describe(&comparison(2.0, 1.9, 2.1), "HEAD")),
+            "HEAD 4.00ms  now 2.00ms  2.00× faster (1.90×–2.10× speedup, 95% CI of 10 runs)"
+        );
+        assert_eq!(
+            strip_ansi(&describe(&comparison(0.5, 0.4, 0.6), "HEAD")),
+            "HEAD 4.00ms  now 2.00ms  2.00× slower (0.40×–0.60× speedup, 95% CI of 10 runs)"
+        );
+        assert!(strip_ansi(&describe(&comparison(1.01, 0.9, 1.1), "HEAD"))
+            .contains("no significant change"));
+    }
+}
@@ -0,0 +1,302 @@
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Checked in at the workspace root
+pub const WORKSPACE_CONFIG: &str = "aoc.toml";
+
+/// Runner settings, from defaults, then `aoc.toml`, then the user config, then CLI flags
+#[derive(Debug, Clone, Serialize)]
+pub struct Config {
+    /// Used when `--year` isn't given
+    pub year: Option<u32>,
+    /// Directory of `YYYY-DD` input files
+    pub inputs: PathBuf,
+    /// Where answers and input fingerprints are recorded
+    pub answers: PathBuf,
+    /// File of the secret encrypted inputs are decrypted with, unless `AOC_INPUT_KEY` is set
+    pub input_key: Option<PathBuf>,
+    /// A private leaderboard's JSON URL, with `{year}` replaced by the year
+    pub leaderboard: Option<String>,
+    /// File of the adventofcode.com session cookie, unless `AOC_SESSION` is set
+    pub session: Option<PathBuf>,
+    pub profile: Profile,
+    pub backend: Backend,
+    /// Wasm instructions a sandboxed solution may run per day, unlimited if unset
+    pub fuel: Option<u64>,
+    /// Memory a sandboxed solution may grow to, in MiB
+    pub memory_mb: u32,
+    /// How many days to run at once, 0 for one per CPU
+    pub jobs: usize,
+    /// Per case, in seconds
+    pub timeout: Option<u64>,
+    pub format: OutputFormat,
+    pub colour: ColourMode,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Profile {
+    Debug,
+    Release,
+}
+
+/// How Rust solutions are run
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    /// Loaded into the runner as shared libraries
+    Native,
+    /// Built for wasm32-wasip1 and run in a sandbox, within `fuel` and `memory_mb`
+    Wasm,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    /// Coloured text from `run_cases`
+    Human,
+    /// JUnit XML, one test case per case run
+    Junit,
+    /// Test Anything Protocol, version 13
+    Tap,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ColourMode {
+    /// When stdout is a terminal and `NO_COLOR` isn't set
+    Auto,
+    Always,
+    Never,
+}
+
+/// One config file, where anything unset falls through to the layer below
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigFile {
+    year: Option<u32>,
+    inputs: Option<PathBuf>,
+    answers: Option<PathBuf>,
+    input_key: Option<PathBuf>,
+    leaderboard: Option<String>,
+    session: Option<PathBuf>,
+    profile: Option<Profile>,
+    backend: Option<Backend>,
+    fuel: Option<u64>,
+    memory_mb: Option<u32>,
+    jobs: Option<usize>,
+    timeout: Option<u64>,
+    format: Option<OutputFormat>,
+    colour: Option<ColourMode>,
+}
+
+impl Config {
+    /// The defaults with every config file that exists applied, and the files that were read
+    pub fn load() -> Result<(Self, Vec<PathBuf>)> {
+        let root = workspace_root()?;
+        let mut config = Self::defaults(&root)?;
+        let mut sources = Vec::new();
+
+        for path in [Some(root.join(WORKSPACE_CONFIG)), user_config_path()]
+            .into_iter()
+            .flatten()
+        {
+            if let Some(file) = ConfigFile::read(&path)? {
+                // relative paths in any layer are relative to the workspace
+                config.apply(file, &root);
+                sources.push(path);
+            }
+        }
+
+        Ok((config, sources))
+    }
+
+    pub fn defaults(root: &Path) -> Result<Self> {
+        Ok(Self {
+            year: None,
+            inputs: root.join("inputs"),
+            answers: root.join("answers.toml"),
+            // kept out of the workspace so it can't be committed with the inputs
+            input_key: user_config_dir().map(|dir| dir.join("input.key")),
+            leaderboard: None,
+            session: user_config_dir().map(|dir| dir.join("session")),
+            profile: Profile::current()?,
+            backend: Backend::Native,
+            fuel: None,
+            memory_mb: 1024,
+            jobs: 1,
+            timeout: None,
+            format: OutputFormat::Human,
+            colour: ColourMode::Auto,
+        })
+    }
+
+    fn apply(&mut self, file: ConfigFile, root: &Path) {
+        let ConfigFile {
+            year,
+            inputs,
+            answers,
+            input_key,
+            leaderboard,
+            session,
+            profile,
+            backend,
+            fuel,
+            memory_mb,
+            jobs,
+            timeout,
+            format,
+            colour,
+        } = file;
+
+        self.year = year.or(self.year);
+        self.inputs = inputs.map_or_else(|| self.inputs.clone(), |p| root.join(p));
+        self.answers = answers.map_or_else(|| self.answers.clone(), |p| root.join(p));
+        self.input_key = input_key.map(|p| root.join(p)).or(self.input_key.take());
+        self.leaderboard = leaderboard.or(self.leaderboard.take());
+        self.session = session.map(|p| root.join(p)).or(self.session.take());
+        self.profile = profile.unwrap_or(self.profile);
+        self.backend = backend.unwrap_or(self.backend);
+        self.fuel = fuel.or(self.fuel);
+        self.memory_mb = memory_mb.unwrap_or(self.memory_mb);
+        self.jobs = jobs.unwrap_or(self.jobs);
+        self.timeout = timeout.or(self.timeout);
+        self.format = format.unwrap_or(self.format);
+        self.colour = colour.unwrap_or(self.colour);
+    }
+
+    pub fn input_path(&self, year: u32, day: u32) -> PathBuf {
+        self.inputs.join(format!("{year}-{day:02}"))
+    }
+
+    /// Where the input is kept encrypted, to be committed
+    pub fn encrypted_input_path(&self, year: u32, day: u32) -> PathBuf {
+        self.inputs.join(format!("{year}-{day:02}.enc"))
+    }
+
+    /// [`Config::jobs`] with 0 resolved to the CPU count
+    pub fn job_count(&self) -> usize {
+        match self.jobs {
+            0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+            jobs => jobs,
+        }
+    }
+
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout.map(Duration::from_secs)
+    }
+
+    /// Never for JUnit or TAP, where output is captured into the report
+    pub fn colour_enabled(&self) -> bool {
+        if self.format != OutputFormat::Human {
+            return false;
+        }
+        match self.colour {
+            ColourMode::Always => true,
+            ColourMode::Never => false,
+            ColourMode::Auto => {
+                std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none()
+            }
+        }
+    }
+}
+
+impl ConfigFile {
+    fn read(path: &Path) -> Result<Option<Self>> {
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        };
+
+        toml::from_str(&text)
+            .with_context(|| format!("Failed to parse {}", path.display()))
+            .map(Some)
+    }
+}
+
+impl Profile {
+    /// The profile the runner itself was built with
+    fn current() -> Result<Self> {
+        let exe = std::env::current_exe().context("Failed to get current exe path")?;
+        // tests run from target/<profile>/deps
+        exe.ancestors()
+            .find_map(|dir| Self::from_dir_name(dir.file_name()?.to_str()?))
+            .context("did not find debug or release in current binary dir")
+    }
+
+    fn from_dir_name(name: &str) -> Option<Self> {
+        match name {
+            "debug" => Some(Self::Debug),
+            "release" => Some(Self::Release),
+            _ => None,
+        }
+    }
+
+    pub fn dir_name(self) -> &'static str {
+        match self {
+            Self::Debug => "debug",
+            Self::Release => "release",
+        }
+    }
+}
+
+/// The runner's parent directory when run through cargo, otherwise the current directory
+pub fn workspace_root() -> Result<PathBuf> {
+    match std::env::var_os("CARGO_MANIFEST_DIR") {
+        Some(dir) => Ok(Path::new(&dir)
+            .parent()
+            .context("runner crate has no parent directory")?
+            .to_owned()),
+        None => std::env::current_dir().context("Failed to get current directory"),
+    }
+}
+
+/// `$XDG_CONFIG_HOME/aoc/config.toml`, falling back to `~/.config`
+fn user_config_path() -> Option<PathBuf> {
+    Some(user_config_dir()?.join("config.toml"))
+}
+
+/// `$XDG_CONFIG_HOME/aoc`, falling back to `~/.config/aoc`
+fn user_config_dir() -> Option<PathBuf> {
+    let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+    Some(config_dir.join("aoc"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layering() {
+        let root = Path::new("/workspace");
+        let mut config = Config::defaults(root).unwrap();
+
+        let workspace: ConfigFile =
+            toml::from_str("year = 2024\ninputs = \"puzzle-inputs\"\njobs = 4").unwrap();
+        let user: ConfigFile = toml::from_str("jobs = 8\ncolour = \"never\"").unwrap();
+        config.apply(workspace, root);
+        config.apply(user, root);
+
+        assert_eq!(config.year, Some(2024));
+        assert_eq!(config.inputs, root.join("puzzle-inputs"));
+        assert_eq!(config.jobs, 8);
+        assert_eq!(config.colour, ColourMode::Never);
+        assert_eq!(config.format, OutputFormat::Human);
+        assert_eq!(
+            config.input_path(2024, 3),
+            root.join("puzzle-inputs/2024-03")
+        );
+    }
+
+    #[test]
+    fn test_unknown_key() {
+        assert!(toml::from_str::<ConfigFile>("yaer = 2024").is_err());
+    }
+}
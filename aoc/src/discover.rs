@@ -0,0 +1,150 @@
+use crate::external::Manifest;
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+/// Copied by `aoc new`, not a solution itself
+pub const TEMPLATE_DIR: &str = "solutions/aoc-YYYY-DD";
+
+#[derive(Debug, Clone)]
+pub struct Discovered {
+    pub year: u32,
+    pub day: u32,
+    pub kind: SolutionKind,
+}
+
+#[derive(Debug, Clone)]
+pub enum SolutionKind {
+    /// A crate under `solutions/`
+    Rust { crate_dir: PathBuf },
+    /// An entry in the external solutions manifest
+    External { command: Vec<String> },
+}
+
+/// Every solution in the workspace, sorted by year and day. External solutions take precedence
+/// over a crate for the same day, as they do when running.
+pub fn discover(root: &Path, manifest: Option<&Manifest>) -> Result<Vec<Discovered>> {
+    let solutions_dir = root.join("solutions");
+    let mut found = Vec::new();
+
+    for entry in std::fs::read_dir(&solutions_dir)
+        .with_context(|| format!("Failed to read {}", solutions_dir.display()))?
+    {
+        let entry = entry?;
+        let Some((year, day)) = entry.file_name().to_str().and_then(parse_crate_name) else {
+            continue;
+        };
+        if manifest.is_some_and(|manifest| manifest.find(year, day).is_some()) {
+            continue;
+        }
+
+        found.push(Discovered {
+            year,
+            day,
+            kind: SolutionKind::Rust {
+                crate_dir: entry.path(),
+            },
+        });
+    }
+
+    for solution in manifest.map(Manifest::solutions).unwrap_or_default() {
+        found.push(Discovered {
+            year: solution.year,
+            day: solution.day,
+            kind: SolutionKind::External {
+                command: solution.command.clone(),
+            },
+        });
+    }
+
+    found.sort_by_key(|solution| (solution.year, solution.day));
+    Ok(found)
+}
+
+/// `aoc-YYYY-DD` to year and day
+fn parse_crate_name(name: &str) -> Option<(u32, u32)> {
+    let (year, day) = name.strip_prefix("aoc-")?.split_once('-')?;
+    Some((year.parse().ok()?, day.parse().ok()?))
+}
+
+pub fn crate_dir(root: &Path, year: u32, day: u32) -> PathBuf {
+    root.join(format!("solutions/aoc-{year}-{day:02}"))
+}
+
+/// The `YYYY-DD.rs` symlink at the workspace root
+pub fn source_link(root: &Path, year: u32, day: u32) -> PathBuf {
+    root.join(format!("{year}-{day:02}.rs"))
+}
+
+/// Copies the template crate with its placeholders filled in, and links its source from the root
+pub fn new_solution(root: &Path, year: u32, day: u32) -> Result<PathBuf> {
+    let target = crate_dir(root, year, day);
+    anyhow::ensure!(
+        !target.exists(),
+        "Solution directory already exists: {}",
+        target.display()
+    );
+
+    copy_template(&root.join(TEMPLATE_DIR), &target, year, day)?;
+
+    let link = source_link(root, year, day);
+    let link_target = target
+        .strip_prefix(root)
+        .expect("crate is in the workspace")
+        .join("src/lib.rs");
+    std::os::unix::fs::symlink(&link_target, &link)
+        .with_context(|| format!("Failed to create symlink {}", link.display()))?;
+
+    Ok(target)
+}
+
+fn copy_template(from: &Path, to: &Path, year: u32, day: u32) -> Result<()> {
+    std::fs::create_dir_all(to).with_context(|| format!("Failed to create {}", to.display()))?;
+
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let dest = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_template(&entry.path(), &dest, year, day)?;
+        } else {
+            let text = std::fs::read_to_string(entry.path())
+                .with_context(|| format!("Failed to read {}", entry.path().display()))?;
+            let text = text
+                .replace("YYYY", &year.to_string())
+                .replace("DD", &format!("{day:02}"));
+            std::fs::write(&dest, text)
+                .with_context(|| format!("Failed to write {}", dest.display()))?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_solution() {
+        let root = std::env::temp_dir().join(format!("aoc-new-{}", std::process::id()));
+        std::fs::remove_dir_all(&root).ok();
+        let template = root.join(TEMPLATE_DIR);
+        std::fs::create_dir_all(template.join("src")).unwrap();
+        std::fs::write(template.join("Cargo.toml"), "name = \"aoc-YYYY-DD\"").unwrap();
+        std::fs::write(template.join("src/lib.rs"), "struct DayDD_YYYY;").unwrap();
+
+        let dir = new_solution(&root, 2024, 3).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(dir.join("Cargo.toml")).unwrap(),
+            "name = \"aoc-2024-03\""
+        );
+        assert_eq!(
+            std::fs::read_to_string(source_link(&root, 2024, 3)).unwrap(),
+            "struct Day03_2024;"
+        );
+        assert!(new_solution(&root, 2024, 3).is_err());
+
+        let found = discover(&root, None).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!((found[0].year, found[0].day), (2024, 3));
+    }
+}
@@ -0,0 +1,268 @@
+//! `aoc examples`: proposes example cases from a saved puzzle page
+
+use crate::cli::ExamplesArgs;
+use crate::config::Config;
+use crate::{cases, discover};
+use anyhow::{Context, Result};
+use common::strip_ansi;
+use log::warn;
+use owo_colors::OwoColorize;
+use std::path::Path;
+
+/// Prefix of the consts inserted inputs are added as
+const EXAMPLE_PREFIX: &str = "EXAMPLE";
+
+/// An example input with an answer the puzzle text gives for it
+#[derive(Debug, PartialEq, Eq)]
+struct Proposal {
+    part: u8,
+    input: String,
+    answer: String,
+}
+
+/// What the page holds, in order
+#[derive(Debug, PartialEq, Eq)]
+enum Item {
+    /// A `<pre><code>` block, and whether the text since the last one introduced an example
+    Block { text: String, introduced: bool },
+    /// Emphasised code, `<code><em>` or `<em><code>`, which is how answers are written
+    Answer(String),
+}
+
+pub fn run(root: &Path, config: &Config, args: ExamplesArgs) -> Result<()> {
+    let page = std::fs::read_to_string(&args.from)
+        .with_context(|| format!("Failed to read {}", args.from.display()))?;
+    let proposals = propose(&page);
+    anyhow::ensure!(
+        !proposals.is_empty(),
+        "found no example with an answer in {}",
+        args.from.display()
+    );
+
+    let colour = config.colour_enabled();
+    let say = |text: String| println!("{}", if colour { text } else { strip_ansi(&text) });
+    for (n, proposal) in proposals.iter().enumerate() {
+        say(format!(
+            "{} part {}, answer {}",
+            format!("{}.", n + 1).bold(),
+            proposal.part,
+            proposal.answer.bright_white().bold()
+        ));
+        for line in proposal.input.lines() {
+            say(format!("    {}", line.bright_black()));
+        }
+    }
+
+    if !args.insert {
+        return Ok(());
+    }
+    let (year, day) = args.day.resolve(config)?;
+    let source = discover::crate_dir(root, year, day).join("src/lib.rs");
+    anyhow::ensure!(
+        source.exists(),
+        "no solution crate for {year} day {day}, create it with `aoc new`"
+    );
+    for (n, proposal) in proposals.iter().enumerate() {
+        if args
+            .pick
+            .as_ref()
+            .is_some_and(|pick| !pick.0.contains(&(n as u32 + 1)))
+        {
+            continue;
+        }
+        let literal = cases::answer_literal(&proposal.answer);
+        let name = cases::insert_example(
+            &source,
+            EXAMPLE_PREFIX,
+            proposal.part,
+            &literal,
+            &proposal.input,
+        )?;
+        warn!(
+            "inserted {}. as example_part{}({literal}, {name}) in {}",
+            n + 1,
+            proposal.part,
+            source.display()
+        );
+    }
+    Ok(())
+}
+
+/// Pairs each answer with the latest block an example was introduced by, or the latest block if
+/// none was, so a part 2 answer about "the example above" gets part 1's input. Each `<article>`
+/// is a part.
+fn propose(page: &str) -> Vec<Proposal> {
+    let articles = page.split("<article").skip(1).collect::<Vec<_>>();
+    let parts = if articles.is_empty() {
+        vec![page]
+    } else {
+        articles
+    };
+
+    let mut proposals = Vec::new();
+    let (mut latest, mut latest_introduced) = (None, None);
+    for (part, article) in (1..=2).zip(parts) {
+        for item in items(article) {
+            match item {
+                Item::Block { text, introduced } => {
+                    if introduced {
+                        latest_introduced = Some(text.clone());
+                    }
+                    latest = Some(text);
+                }
+                Item::Answer(answer) => {
+                    let Some(input) = latest_introduced.as_ref().or(latest.as_ref()) else {
+                        continue;
+                    };
+                    let proposal = Proposal {
+                        part,
+                        input: input.trim_end_matches('\n').to_owned(),
+                        answer,
+                    };
+                    if !proposals.contains(&proposal) {
+                        proposals.push(proposal);
+                    }
+                }
+            }
+        }
+    }
+    proposals
+}
+
+fn items(html: &str) -> Vec<Item> {
+    let mut items = Vec::new();
+    let mut prose = String::new();
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        prose.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let (opening, closing) = if rest.starts_with("<pre><code>") {
+            ("<pre><code>", "</code></pre>")
+        } else if rest.starts_with("<code><em>") {
+            ("<code><em>", "</em></code>")
+        } else if rest.starts_with("<em><code>") {
+            ("<em><code>", "</code></em>")
+        } else {
+            rest = rest.find('>').map_or("", |end| &rest[end + 1..]);
+            continue;
+        };
+
+        let body = &rest[opening.len()..];
+        let Some(end) = body.find(closing) else {
+            break;
+        };
+        let text = text(&body[..end]);
+        if opening == "<pre><code>" {
+            let introduced = prose.to_lowercase().contains("example");
+            items.push(Item::Block { text, introduced });
+            prose.clear();
+        } else {
+            prose.push_str(&text);
+            items.push(Item::Answer(text));
+        }
+        rest = &body[end + closing.len()..];
+    }
+    items
+}
+
+/// The text of some HTML, without its tags and with entities decoded
+fn text(html: &str) -> String {
+    let mut without_tags = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        without_tags.push_str(&rest[..start]);
+        rest = rest[start..]
+            .find('>')
+            .map_or("", |end| &rest[start + end + 1..]);
+    }
+    without_tags.push_str(rest);
+
+    let mut text = String::with_capacity(without_tags.len());
+    let mut rest = without_tags.as_str();
+    while let Some(start) = rest.find('&') {
+        text.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let decoded = rest.find(';').filter(|&end| end <= 10).and_then(|end| {
+            let c = match &rest[1..end] {
+                "lt" => '<',
+                "gt" => '>',
+                "amp" => '&',
+                "quot" => '"',
+                "apos" => '\'',
+                "nbsp" => ' ',
+                entity => {
+                    let code = match entity.strip_prefix("#x").or(entity.strip_prefix("#X")) {
+                        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                        None => entity.strip_prefix('#')?.parse().ok(),
+                    };
+                    char::from_u32(code?)?
+                }
+            };
+            Some((c, end + 1))
+        });
+        match decoded {
+            Some((c, len)) => {
+                text.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                text.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    text.push_str(rest);
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<main>
+<article class="day-desc"><h2>--- Day 1: Historian Hysteria ---</h2>
+<p>For example:</p>
+<pre><code>3   4
+4   3
+</code></pre>
+<p>The smallest number in the left list is <code>1</code>, in the <em>right</em> list it's
+<code>3</code>. Here they are:</p>
+<pre><code>1 3
+</code></pre>
+<p>The total distance is <code><em>11</em></code>.</p>
+</article>
+<p>Your puzzle answer was <code>1882714</code>.</p>
+<article class="day-desc"><h2 id="part2">--- Part Two ---</h2>
+<p>In the example above, the similarity score is <em><code>3*4</code></em>.</p>
+<p>A new example with a tag inside:</p>
+<pre><code>a &lt;<em>b</em>&gt; &amp;&#35;&#x41;
+</code></pre>
+<p>Which is <code><em>31</em></code>, or <code><em>31</em></code> again.</p>
+</article>
+</main>"#;
+
+    #[test]
+    fn test_propose() {
+        let proposal = |part, input: &str, answer: &str| Proposal {
+            part,
+            input: input.to_owned(),
+            answer: answer.to_owned(),
+        };
+        assert_eq!(
+            propose(PAGE),
+            [
+                proposal(1, "3   4\n4   3", "11"),
+                proposal(2, "3   4\n4   3", "3*4"),
+                proposal(2, "a <b> &#A", "31"),
+            ]
+        );
+        assert_eq!(
+            propose("<p>For example:</p><pre><code>x</code></pre><code><em>&amp;</em></code>"),
+            [proposal(1, "x", "&")]
+        );
+        assert_eq!(
+            propose("<code><em>1</em></code><pre><code>x</code></pre>"),
+            []
+        );
+    }
+}
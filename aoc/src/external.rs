@@ -0,0 +1,375 @@
+use anyhow::{bail, Context, Result};
+use common::{
+    run_case_table, CaseError, ImplSelection, InputPolicy, MatchPolicy, ParseError, PartNumber,
+    RunConfig, SolutionInput, SolutionResult,
+};
+use serde::Deserialize;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_MANIFEST: &str = "solutions-nonrust/manifest.toml";
+
+/// Solutions that aren't Rust crates, run as a program per case with the input on stdin and the
+/// part ("1" or "2") as the last argument. The last non-empty line of stdout is the answer.
+#[derive(Debug, Deserialize)]
+pub struct Manifest {
+    #[serde(default, rename = "solution")]
+    solutions: Vec<ExternalSolution>,
+    /// Commands run from here, the manifest's directory
+    #[serde(skip)]
+    dir: PathBuf,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExternalSolution {
+    pub year: u32,
+    pub day: u32,
+    pub command: Vec<String>,
+    /// How inputs and examples are tidied before the program gets them
+    #[serde(default)]
+    input: InputPolicy,
+    /// Both parts against the full input if empty
+    #[serde(default, rename = "case")]
+    cases: Vec<ExternalCase>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExternalCase {
+    part: u8,
+    /// Full input if not set
+    example: Option<String>,
+    answer: Option<Answer>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Answer {
+    Int(i64),
+    String(String),
+}
+
+impl Manifest {
+    /// `None` if there is no manifest
+    pub fn load(path: &Path) -> Result<Option<Self>> {
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        };
+
+        let mut manifest: Self =
+            toml::from_str(&text).with_context(|| format!("Failed to parse {}", path.display()))?;
+        manifest.dir = path.parent().unwrap_or(Path::new(".")).to_owned();
+        Ok(Some(manifest))
+    }
+
+    pub fn find(&self, year: u32, day: u32) -> Option<&ExternalSolution> {
+        self.solutions
+            .iter()
+            .find(|solution| solution.year == year && solution.day == day)
+    }
+
+    pub fn solutions(&self) -> &[ExternalSolution] {
+        &self.solutions
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+}
+
+impl ExternalSolution {
+    /// The first argument of `command` that's a file in `dir`, probably the script
+    pub fn source(&self, dir: &Path) -> Option<PathBuf> {
+        self.command
+            .iter()
+            .map(|arg| dir.join(arg))
+            .find(|path| path.is_file())
+    }
+
+    /// Runs the cases through the same table as Rust solutions, returning if they all passed
+    pub fn run(
+        &self,
+        dir: &Path,
+        input: &str,
+        config: &RunConfig,
+        timeout: Option<Duration>,
+    ) -> Result<bool> {
+        let Some((program, args)) = self.command.split_first() else {
+            bail!("empty command for {}-{:02}", self.year, self.day);
+        };
+
+        if let ImplSelection::Named(name) = &config.implementation {
+            bail!("external solutions have no implementation named {name:?}");
+        }
+
+        let cases = self.case_table()?;
+        let solve = |input: &str, part: PartNumber| match run_program(
+            dir, program, args, input, part, timeout,
+        ) {
+            Ok(Some(result)) => Ok(result),
+            Ok(None) => Err(CaseError::Timeout(
+                timeout.expect("only times out with a timeout"),
+            )),
+            Err(e) => Err(CaseError::Parse(ParseError::new(format!("{e:#}")))),
+        };
+
+        Ok(run_case_table(
+            input,
+            &cases,
+            &solve,
+            &[],
+            MatchPolicy::Canonical,
+            self.input,
+            config,
+        ))
+    }
+
+    fn case_table(&self) -> Result<Vec<(PartNumber, SolutionInput, Option<SolutionResult>)>> {
+        if self.cases.is_empty() {
+            return Ok(vec![
+                (PartNumber::Part1, SolutionInput::FullInput, None),
+                (PartNumber::Part2, SolutionInput::FullInput, None),
+            ]);
+        }
+
+        self.cases
+            .iter()
+            .map(|case| {
+                let part = match case.part {
+                    1 => PartNumber::Part1,
+                    2 => PartNumber::Part2,
+                    n => bail!("invalid part {n} in manifest"),
+                };
+                // the manifest lives for the whole run anyway
+                let input = match &case.example {
+                    Some(example) => SolutionInput::Example(example.clone().leak()),
+                    None => SolutionInput::FullInput,
+                };
+                let answer = case.answer.as_ref().map(|answer| match answer {
+                    Answer::Int(n) => SolutionResult::from(*n),
+                    Answer::String(s) => SolutionResult::from(s.as_str()),
+                });
+
+                Ok((part, input, answer))
+            })
+            .collect()
+    }
+}
+
+fn run_program(
+    dir: &Path,
+    program: &str,
+    args: &[String],
+    input: &str,
+    part: PartNumber,
+    timeout: Option<Duration>,
+) -> Result<Option<SolutionResult>> {
+    let mut child = Command::new(program)
+        .args(args)
+        .arg((part as u8).to_string())
+        .current_dir(dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .with_context(|| format!("Failed to run {program}"))?;
+
+    // written and read on other threads so a program that prints before reading all its input
+    // can't deadlock on a full pipe. They're left behind if it times out, as anything it started
+    // may still hold the pipes open.
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let mut stdout = child.stdout.take().expect("stdout is piped");
+    let input = input.to_owned();
+    std::thread::spawn(move || {
+        // the program is free to stop reading early. Inputs are trimmed, but line-based readers
+        // expect the last line to be terminated like it is in the input file
+        stdin.write_all(input.as_bytes()).ok();
+        if !input.ends_with('\n') {
+            stdin.write_all(b"\n").ok();
+        }
+    });
+    let reader = std::thread::spawn(move || {
+        let mut output = Vec::new();
+        stdout.read_to_end(&mut output).map(|_| output)
+    });
+
+    let status = wait_with_timeout(&mut child, timeout)
+        .with_context(|| format!("Failed to wait for {program}"))?;
+    let Some(status) = status else {
+        return Ok(None);
+    };
+    if !status.success() {
+        bail!("{program} failed: {status}");
+    }
+
+    let stdout = reader
+        .join()
+        .expect("stdout reader panicked")
+        .with_context(|| format!("Failed to read output of {program}"))?;
+
+    parse_answer(&String::from_utf8_lossy(&stdout)).map(Some)
+}
+
+/// `None` if the program was killed for running over
+fn wait_with_timeout(child: &mut Child, timeout: Option<Duration>) -> Result<Option<ExitStatus>> {
+    let Some(timeout) = timeout else {
+        return Ok(Some(child.wait()?));
+    };
+
+    let start = Instant::now();
+    while start.elapsed() < timeout {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(status));
+        }
+        std::thread::sleep(Duration::from_millis(5));
+    }
+
+    child.kill()?;
+    child.wait()?;
+    Ok(None)
+}
+
+fn parse_answer(stdout: &str) -> Result<SolutionResult> {
+    let Some(line) = stdout.lines().map(str::trim).rfind(|l| !l.is_empty()) else {
+        bail!("no answer printed");
+    };
+
+    Ok(match line.parse::<i128>() {
+        Ok(n) => SolutionResult::from(n),
+        Err(_) => SolutionResult::from(line),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn config() -> RunConfig {
+        RunConfig {
+            part_filter: None,
+            case_filter: 0,
+            solutions_only: false,
+            implementation: ImplSelection::Default,
+            bench: false,
+            viz_sink: None,
+            viz_every: 1,
+            progress_sink: None,
+            report_sink: None,
+            colour: false,
+            timeout: None,
+            input_changed: false,
+        }
+    }
+
+    /// Writes a manifest and a `solve.sh` into a fresh directory, removed when it's dropped
+    fn manifest(script: &str, cases: &str) -> (TempDir, Manifest) {
+        let temp = TempDir::with_prefix("aoc-external-").unwrap();
+        let dir = temp.path();
+        std::fs::write(dir.join("solve.sh"), script).unwrap();
+
+        let path = dir.join("manifest.toml");
+        std::fs::write(
+            &path,
+            format!(
+                "[[solution]]\nyear = 2024\nday = 1\ncommand = [\"sh\", \"solve.sh\"]\n{cases}"
+            ),
+        )
+        .unwrap();
+
+        (temp, Manifest::load(&path).unwrap().unwrap())
+    }
+
+    fn run(manifest: &Manifest, input: &str) -> bool {
+        manifest
+            .find(2024, 1)
+            .unwrap()
+            .run(manifest.dir(), input, &config(), None)
+            .unwrap()
+    }
+
+    const SUM: &str = r#"
+total=0
+while read -r n; do total=$((total + n * $1)); done
+echo "debug output"
+echo "$total"
+"#;
+
+    #[test]
+    fn test_passing_cases() {
+        let (_dir, manifest) = manifest(
+            SUM,
+            r#"
+[[solution.case]]
+part = 1
+example = "1\n2\n3"
+answer = 6
+
+[[solution.case]]
+part = 2
+answer = 20
+"#,
+        );
+
+        assert!(run(&manifest, "4\n6"));
+    }
+
+    #[test]
+    fn test_wrong_answer() {
+        let (_dir, manifest) = manifest(
+            SUM,
+            r#"
+[[solution.case]]
+part = 1
+answer = 11
+"#,
+        );
+
+        assert!(!run(&manifest, "4\n6"));
+    }
+
+    #[test]
+    fn test_failing_program() {
+        let (_dir, manifest) = manifest(
+            "echo 5; exit 1",
+            r#"
+[[solution.case]]
+part = 1
+answer = 5
+"#,
+        );
+
+        assert!(!run(&manifest, ""));
+    }
+
+    #[test]
+    fn test_timeout() {
+        let (_dir, manifest) = manifest("sleep 10; echo 1", "");
+        let solution = manifest.find(2024, 1).unwrap();
+
+        let start = Instant::now();
+        let passed = solution
+            .run(
+                manifest.dir(),
+                "",
+                &config(),
+                Some(Duration::from_millis(100)),
+            )
+            .unwrap();
+        assert!(!passed);
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_parse_answer() {
+        assert_eq!(parse_answer("12\n\n").unwrap(), SolutionResult::from(12));
+        assert_eq!(
+            parse_answer("working...\nabc,def\n").unwrap(),
+            SolutionResult::from("abc,def")
+        );
+        assert!(parse_answer("\n").is_err());
+    }
+}
@@ -0,0 +1,118 @@
+use crate::cli::FuzzArgs;
+use crate::config::Config;
+use crate::report::Capture;
+use crate::{cases, discover, ensure_solution_built, library_path};
+use anyhow::{Context, Result};
+use common::fuzz::{FuzzEntrypointFn, FuzzOptions, FuzzOutcome, FuzzReport};
+use common::strip_ansi;
+use libloading::{Library, Symbol};
+use log::{info, warn};
+use owo_colors::OwoColorize;
+use std::ffi::c_void;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Prefix of the consts failing inputs are saved as
+const EXAMPLE_PREFIX: &str = "FUZZ_EXAMPLE";
+
+/// Fuzzes a day's targets, adding any shrunk failing inputs to its example cases
+pub fn run(root: &Path, config: &Config, args: FuzzArgs) -> Result<()> {
+    let (year, day) = args.day.resolve(config)?;
+    let source = discover::crate_dir(root, year, day).join("src/lib.rs");
+    anyhow::ensure!(
+        source.exists(),
+        "no solution crate for {year} day {day}, only Rust solutions can be fuzzed"
+    );
+
+    ensure_solution_built(config, year, day).context("Failed to ensure solution is built")?;
+    let seed = args.seed.unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |t| t.as_nanos() as u64)
+    });
+    info!("fuzzing {year} day {day} with seed {seed}");
+
+    let mut reports = Vec::new();
+    let options = FuzzOptions {
+        part_filter: args.part.unwrap_or(0),
+        iterations: args.iterations,
+        seed,
+        report_sink: Some(sink),
+        report_ctx: &mut reports as *mut Vec<FuzzReport> as *mut c_void,
+    };
+
+    unsafe {
+        let lib = Library::new(library_path(config, year, day)?)
+            .context("Failed to load solution library")?;
+        let func: Symbol<FuzzEntrypointFn> = lib
+            .get(b"fuzz_entrypoint")
+            .context("Failed to load fuzz_entrypoint symbol")?;
+
+        // solutions' own debug output would drown out the results
+        let capture = Capture::start(false)?;
+        func(&options);
+        capture.stop();
+    }
+
+    if reports.is_empty() {
+        anyhow::bail!("{year} day {day} has no fuzz targets, add them in `Solution::fuzz_targets`");
+    }
+
+    let colour = config.colour_enabled();
+    let say = |text: String| println!("{}", if colour { text } else { strip_ansi(&text) });
+    for FuzzReport { part, outcome } in reports {
+        match outcome {
+            FuzzOutcome::Passed { tried, skipped } => say(format!(
+                "   ✓ {} part {part}: {} inputs agreed ({skipped} skipped)",
+                "FUZZ".green().bold(),
+                tried - skipped
+            )),
+            FuzzOutcome::Failed {
+                after,
+                input,
+                expected,
+                got,
+                expected_literal,
+            } => {
+                say(format!(
+                    "   ✗ {} part {part} after {after} inputs: expected {}, got {}",
+                    "FUZZ".red().bold(),
+                    expected.bright_yellow().bold(),
+                    got.bright_red().bold()
+                ));
+                for line in input.lines() {
+                    println!("      {line}");
+                }
+
+                if args.no_save {
+                    continue;
+                }
+                let name = cases::insert_example(
+                    &source,
+                    EXAMPLE_PREFIX,
+                    part,
+                    &expected_literal,
+                    &input,
+                )?;
+                warn!("saved as {name} in {}", source.display());
+            }
+            FuzzOutcome::GeneratorPanicked { after, message } => say(format!(
+                "   ✗ {} part {part}: generator panicked on input {after}: {}",
+                "FUZZ".red().bold(),
+                message.bright_red()
+            )),
+        }
+    }
+
+    Ok(())
+}
+
+/// # Safety
+/// `ctx` must be the `Vec<FuzzReport>` passed in [`FuzzOptions`].
+unsafe extern "C" fn sink(ctx: *mut c_void, report_ptr: *const u8, report_len: usize) {
+    let reports = &mut *(ctx as *mut Vec<FuzzReport>);
+    let bytes = std::slice::from_raw_parts(report_ptr, report_len);
+    if let Ok(report) = FuzzReport::decode(bytes) {
+        reports.push(report);
+    }
+}
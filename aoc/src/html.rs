@@ -0,0 +1,216 @@
+use crate::report::{escape, failure_type, DayReport};
+use common::itertools::Itertools;
+use common::report::Outcome;
+use common::strip_ansi;
+use std::fmt::Write as _;
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+
+const STYLE: &str = "
+body { font-family: system-ui, sans-serif; margin: 2em; background: #0f0f23; color: #ccc; }
+h1, h2 { color: #ffff66; font-weight: normal; }
+a { color: #009900; }
+table { border-collapse: collapse; width: 100%; margin-bottom: 2em; }
+th, td { padding: 0.25em 0.75em; text-align: left; vertical-align: top; }
+th { border-bottom: 1px solid #444; }
+tr.day td { padding-top: 1em; border-bottom: 1px solid #333; color: #fff; }
+td.answer { font-family: monospace; }
+.pass { color: #00cc00; }
+.unchecked { color: #ccc; }
+.wrong-answer, .disagreement { color: #ff4444; }
+.error, .panic, .timeout, .setup { color: #ff9900; }
+td.time { width: 20em; }
+.bar { display: inline-block; height: 0.8em; background: #336; margin-right: 0.5em; }
+.redacted { color: #666; }
+pre { background: #10101a; padding: 0.5em; max-height: 30em; overflow: auto; }
+";
+
+/// A self-contained page of every case run, with a table per year. Links to solution sources
+/// are relative to where the page is written.
+pub fn render(days: &[DayReport], report_path: &Path, redact: bool) -> String {
+    let total = days.iter().map(|day| day.cases.len()).sum::<usize>();
+    let passed = days
+        .iter()
+        .flat_map(|day| &day.cases)
+        .filter(|case| failure_type(case.report.outcome).is_none())
+        .count();
+
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>Advent of Code results</title>\n<style>{STYLE}</style>\n</head>\n<body>\n\
+         <h1>Advent of Code results</h1>\n<p>{passed} of {total} cases passed</p>\n"
+    );
+
+    let report_dir = report_path.parent().unwrap_or(Path::new("."));
+    for (year, days) in &days.iter().chunk_by(|day| day.year) {
+        let days = days.collect_vec();
+        year_table(&mut html, year, &days, report_dir, redact);
+    }
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+fn year_table(html: &mut String, year: u32, days: &[&DayReport], report_dir: &Path, redact: bool) {
+    // bars are log scale from a microsecond up to the slowest case of the year
+    let slowest = days
+        .iter()
+        .flat_map(|day| &day.cases)
+        .filter_map(|case| case.report.elapsed_secs)
+        .fold(1e-6, f64::max);
+    let bar_width = |secs: f64| (secs / 1e-6).max(1.0).ln() / (slowest / 1e-6).ln().max(1.0);
+
+    writeln!(html, "<h2>{year}</h2>\n<table>").unwrap();
+    html.push_str(
+        "<tr><th>Part</th><th>Case</th><th>Input</th><th>Status</th><th>Answer</th>\
+         <th>Expected</th><th>Time</th><th>Log</th></tr>\n",
+    );
+
+    for day in days {
+        let name = format!("{}-{:02}", day.year, day.day);
+        let title = match &day.source {
+            Some(source) => format!(
+                "<a href=\"{}\">{name}</a>",
+                escape(&relative_to(source, report_dir).to_string_lossy())
+            ),
+            None => name,
+        };
+        let memory = day
+            .peak_rss_kb
+            .map(|kb| format!(" &middot; peak memory {:.1} MiB", kb as f64 / 1024.0))
+            .unwrap_or_default();
+        writeln!(
+            html,
+            "<tr class=\"day\"><td colspan=\"8\">{title}{memory}</td></tr>"
+        )
+        .unwrap();
+
+        if let Some(error) = &day.error {
+            writeln!(
+                html,
+                "<tr><td></td><td></td><td></td><td class=\"setup\">setup</td>\
+                 <td colspan=\"4\">{}</td></tr>",
+                escape(error)
+            )
+            .unwrap();
+        }
+
+        for case in &day.cases {
+            let report = &case.report;
+            let status = failure_type(report.outcome).unwrap_or(match report.outcome {
+                Outcome::Pass => "pass",
+                _ => "unchecked",
+            });
+            let input = match (&report.implementation, report.full_input) {
+                (Some(implementation), true) => format!("input [{}]", escape(implementation)),
+                (Some(implementation), false) => format!("example [{}]", escape(implementation)),
+                (None, true) => "input".to_owned(),
+                (None, false) => "example".to_owned(),
+            };
+            let answer = |answer: &Option<String>| match answer {
+                Some(_) if redact && report.full_input => {
+                    "<span class=\"redacted\">redacted</span>".to_owned()
+                }
+                Some(answer) => escape(answer),
+                None => String::new(),
+            };
+            let answer_cell = match (&report.answer, &report.message) {
+                (None, Some(message)) => escape(message),
+                _ => answer(&report.answer),
+            };
+            let time = report
+                .elapsed_secs
+                .map(|secs| {
+                    format!(
+                        "<span class=\"bar\" style=\"width: {:.1}%\"></span>{:.2?}",
+                        bar_width(secs) * 70.0,
+                        Duration::from_secs_f64(secs)
+                    )
+                })
+                .unwrap_or_default();
+            let output = strip_ansi(case.output.trim_matches('\n'));
+            let log = if output.trim().is_empty() {
+                String::new()
+            } else if redact && report.full_input {
+                // solutions tend to print their answers along the way
+                "<span class=\"redacted\">log redacted</span>".to_owned()
+            } else {
+                format!(
+                    "<details><summary>log</summary><pre>{}</pre></details>",
+                    escape(&output)
+                )
+            };
+
+            writeln!(
+                html,
+                "<tr><td>{}</td><td>#{}</td><td>{input}</td><td class=\"{status}\">{status}</td>\
+                 <td class=\"answer\">{answer_cell}</td><td class=\"answer\">{}</td>\
+                 <td class=\"time\">{time}</td><td>{log}</td></tr>",
+                report.part,
+                report.case,
+                answer(&report.expected),
+            )
+            .unwrap();
+        }
+    }
+
+    html.push_str("</table>\n");
+}
+
+/// `path` as seen from `base`, both absolute
+fn relative_to(path: &Path, base: &Path) -> PathBuf {
+    let path = path.components().collect_vec();
+    let base = base.components().collect_vec();
+    let common = path.iter().zip(&base).take_while(|(a, b)| a == b).count();
+
+    base[common..]
+        .iter()
+        .map(|_| Component::ParentDir)
+        .chain(path[common..].iter().copied())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::CapturedCase;
+    use common::report::CaseReport;
+
+    #[test]
+    fn test_render() {
+        let days = [DayReport {
+            year: 2024,
+            day: 3,
+            cases: vec![CapturedCase {
+                report: CaseReport {
+                    case: 2,
+                    part: 1,
+                    full_input: true,
+                    implementation: None,
+                    outcome: Outcome::WrongAnswer,
+                    answer: Some("12".to_owned()),
+                    expected: Some("48".to_owned()),
+                    message: None,
+                    elapsed_secs: Some(0.001),
+                },
+                output: "\x1b[31mdebug <x>\x1b[0m\nexpected 48\n".to_owned(),
+            }],
+            error: None,
+            source: Some(PathBuf::from("/ws/solutions/aoc-2024-03/src/lib.rs")),
+            peak_rss_kb: Some(2048),
+            input_hash: None,
+        }];
+
+        let html = render(&days, Path::new("/ws/target/report.html"), false);
+        assert!(html.contains("<a href=\"../solutions/aoc-2024-03/src/lib.rs\">2024-03</a>"));
+        assert!(html.contains("peak memory 2.0 MiB"));
+        assert!(html.contains("<td class=\"wrong-answer\">wrong-answer</td>"));
+        assert!(html.contains("<pre>debug &lt;x&gt;&#10;expected 48</pre>"));
+        assert!(html.contains("<td class=\"answer\">48</td>"));
+
+        let html = render(&days, Path::new("/ws/report.html"), true);
+        assert!(html.contains("href=\"solutions/aoc-2024-03/src/lib.rs\""));
+        assert!(!html.contains("48"));
+        assert!(html.contains("log redacted"));
+    }
+}
@@ -0,0 +1,280 @@
+//! Puzzle inputs on disk, plain or encrypted, and fingerprints tying them to recorded answers
+
+use crate::answers::Answers;
+use crate::cli::{InputsCommand, Selection};
+use crate::config::Config;
+use anyhow::{Context, Result};
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Nonce};
+use log::{info, warn};
+use sha2::{Digest, Sha256};
+use std::fmt::Write as _;
+use std::io::ErrorKind;
+use std::path::Path;
+
+/// Holds the input key itself, overriding the keyfile
+pub const KEY_VAR: &str = "AOC_INPUT_KEY";
+
+/// Starts an encrypted input, followed by the nonce and then the ciphertext
+const MAGIC: &[u8] = b"aoc-input-v1\n";
+const NONCE_LEN: usize = 12;
+
+/// The plain input, or if there isn't one, the decrypted `.enc` beside where it would be
+pub fn read(config: &Config, year: u32, day: u32) -> Result<String> {
+    let path = config.input_path(year, day);
+    match std::fs::read_to_string(&path) {
+        Err(e) if e.kind() == ErrorKind::NotFound => {
+            let encrypted = config.encrypted_input_path(year, day);
+            if !encrypted.exists() {
+                return Err(e)
+                    .with_context(|| format!("Failed to read input from {}", path.display()));
+            }
+            info!("decrypting input from {}", encrypted.display());
+            decrypt_file(&Key::load(config)?, &encrypted)
+        }
+        result => result.with_context(|| format!("Failed to read input from {}", path.display())),
+    }
+}
+
+pub fn run(config: &Config, command: InputsCommand) -> Result<()> {
+    match command {
+        InputsCommand::Encrypt { year } => encrypt_all(config, year.as_ref()),
+        InputsCommand::Decrypt { year, force } => decrypt_all(config, year.as_ref(), force),
+    }
+}
+
+/// Writes a `.enc` beside every plain input
+fn encrypt_all(config: &Config, years: Option<&Selection>) -> Result<()> {
+    let key = Key::load(config)?;
+    let inputs = list(config, false, years)?;
+    for &(year, day) in &inputs {
+        let plain = read_plain(&config.input_path(year, day))?;
+        let path = config.encrypted_input_path(year, day);
+        let encrypted = key.encrypt(&plain);
+        if std::fs::read(&path).is_ok_and(|existing| existing == encrypted) {
+            println!("{year}-{day:02}  unchanged");
+            continue;
+        }
+        std::fs::write(&path, encrypted)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        println!("{year}-{day:02}  encrypted");
+    }
+
+    if inputs.is_empty() {
+        println!("no plain inputs in {}", config.inputs.display());
+    }
+    Ok(())
+}
+
+/// Writes out every `.enc` input as plain text, leaving different plain copies alone unless
+/// `force`
+fn decrypt_all(config: &Config, years: Option<&Selection>, force: bool) -> Result<()> {
+    let key = Key::load(config)?;
+    let inputs = list(config, true, years)?;
+    let mut kept = 0;
+    for &(year, day) in &inputs {
+        let plain = decrypt_file(&key, &config.encrypted_input_path(year, day))?;
+        let path = config.input_path(year, day);
+        match std::fs::read_to_string(&path) {
+            Ok(existing) if existing == plain => {
+                println!("{year}-{day:02}  unchanged");
+                continue;
+            }
+            Ok(_) if !force => {
+                println!("{year}-{day:02}  differs from the plain input, kept it");
+                kept += 1;
+                continue;
+            }
+            _ => {}
+        }
+        std::fs::write(&path, plain)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        println!("{year}-{day:02}  decrypted");
+    }
+
+    if inputs.is_empty() {
+        println!("no encrypted inputs in {}", config.inputs.display());
+    }
+    if kept > 0 {
+        warn!("{kept} plain inputs differ from their encrypted copies, `--force` overwrites them");
+    }
+    Ok(())
+}
+
+/// Years and days of the plain or encrypted inputs present, in order
+fn list(config: &Config, encrypted: bool, years: Option<&Selection>) -> Result<Vec<(u32, u32)>> {
+    let entries = std::fs::read_dir(&config.inputs)
+        .with_context(|| format!("Failed to list {}", config.inputs.display()))?;
+    let mut inputs = Vec::new();
+    for entry in entries {
+        let name = entry?.file_name();
+        let Some((year, day, is_encrypted)) = name.to_str().and_then(parse_name) else {
+            continue;
+        };
+        if is_encrypted == encrypted && years.is_none_or(|Selection(years)| years.contains(&year)) {
+            inputs.push((year, day));
+        }
+    }
+    inputs.sort_unstable();
+    Ok(inputs)
+}
+
+/// `YYYY-DD` or `YYYY-DD.enc`
+fn parse_name(name: &str) -> Option<(u32, u32, bool)> {
+    let (stem, encrypted) = match name.strip_suffix(".enc") {
+        Some(stem) => (stem, true),
+        None => (name, false),
+    };
+    let (year, day) = stem.split_once('-')?;
+    if year.len() != 4 || day.len() != 2 {
+        return None;
+    }
+    Some((year.parse().ok()?, day.parse().ok()?, encrypted))
+}
+
+fn read_plain(path: &Path) -> Result<String> {
+    std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))
+}
+
+fn decrypt_file(key: &Key, path: &Path) -> Result<String> {
+    let bytes =
+        std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    key.decrypt(&bytes)
+        .with_context(|| format!("Failed to decrypt {}", path.display()))
+}
+
+/// Encrypts inputs with ChaCha20-Poly1305, keyed by a hash of the secret
+struct Key(chacha20poly1305::Key);
+
+impl Key {
+    /// From [`KEY_VAR`] or else the configured keyfile, either holding any secret text
+    fn load(config: &Config) -> Result<Self> {
+        let secret = match std::env::var(KEY_VAR) {
+            Ok(secret) => secret,
+            Err(_) => {
+                let path = config
+                    .input_key
+                    .as_ref()
+                    .with_context(|| format!("no input key, set {KEY_VAR} or `input_key`"))?;
+                std::fs::read_to_string(path).with_context(|| {
+                    format!(
+                        "Failed to read input key from {}, create it or set {KEY_VAR}",
+                        path.display()
+                    )
+                })?
+            }
+        };
+        let secret = secret.trim();
+        anyhow::ensure!(!secret.is_empty(), "input key is empty");
+        Ok(Self::from_secret(secret))
+    }
+
+    fn from_secret(secret: &str) -> Self {
+        Self(chacha20poly1305::Key::clone_from_slice(&Sha256::digest(
+            secret.as_bytes(),
+        )))
+    }
+
+    fn encrypt(&self, plain: &str) -> Vec<u8> {
+        // derived from the input, so re-encrypting an unchanged input leaves the file as it was
+        let hash = Sha256::new()
+            .chain_update(self.0)
+            .chain_update(plain)
+            .finalize();
+        let nonce = Nonce::from_slice(&hash[..NONCE_LEN]);
+        let ciphertext = ChaCha20Poly1305::new(&self.0)
+            .encrypt(nonce, plain.as_bytes())
+            .expect("encrypting in memory can't fail");
+        [MAGIC, nonce.as_slice(), &ciphertext].concat()
+    }
+
+    fn decrypt(&self, bytes: &[u8]) -> Result<String> {
+        let rest = bytes
+            .strip_prefix(MAGIC)
+            .context("not an encrypted input")?;
+        anyhow::ensure!(rest.len() >= NONCE_LEN, "encrypted input is truncated");
+        let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+        let plain = ChaCha20Poly1305::new(&self.0)
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| anyhow::anyhow!("wrong key, or the file is corrupt"))?;
+        String::from_utf8(plain).context("decrypted input isn't UTF-8")
+    }
+}
+
+/// Content hash of an input as it is on disk, before any trimming
+pub fn fingerprint(input: &str) -> String {
+    let digest = Sha256::digest(input.as_bytes());
+    let mut hex = String::from("sha256:");
+    for byte in digest {
+        write!(hex, "{byte:02x}").unwrap();
+    }
+    hex
+}
+
+/// Checks every fingerprinted input against its recorded fingerprint, failing if any changed or
+/// went missing
+pub fn verify(config: &Config, answers: &Answers, years: Option<&Selection>) -> Result<()> {
+    let mut bad = 0;
+    let mut checked = 0;
+    for (year, day, expected) in answers.fingerprints() {
+        if years.is_some_and(|Selection(years)| !years.contains(&year)) {
+            continue;
+        }
+
+        checked += 1;
+        let status = match read(config, year, day) {
+            Ok(input) if fingerprint(&input) == expected => "ok",
+            Ok(_) => "changed since answers were recorded",
+            Err(_) => "missing",
+        };
+        if status != "ok" {
+            bad += 1;
+        }
+        println!("{year}-{day:02}  {status}");
+    }
+
+    anyhow::ensure!(
+        bad == 0,
+        "{bad} of {checked} inputs don't match their answers"
+    );
+    if checked == 0 {
+        println!("no inputs fingerprinted yet, they are once a full input case passes");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fingerprint() {
+        assert_eq!(
+            fingerprint("abc"),
+            "sha256:ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_ne!(fingerprint("1\n2\n"), fingerprint("1\n2"));
+    }
+
+    #[test]
+    fn test_encryption() {
+        let key = Key::from_secret("hunter2");
+        let encrypted = key.encrypt("1 2\n3 4\n");
+        assert!(encrypted.starts_with(MAGIC));
+        assert_eq!(key.encrypt("1 2\n3 4\n"), encrypted);
+        assert_ne!(
+            key.encrypt("1 2\n3 5\n")[..MAGIC.len() + NONCE_LEN],
+            encrypted[..MAGIC.len() + NONCE_LEN]
+        );
+        assert_eq!(key.decrypt(&encrypted).unwrap(), "1 2\n3 4\n");
+
+        assert!(Key::from_secret("hunter3").decrypt(&encrypted).is_err());
+        assert!(key.decrypt(&encrypted[..encrypted.len() - 1]).is_err());
+        assert!(key.decrypt(b"1 2\n").is_err());
+
+        assert_eq!(parse_name("2024-07.enc"), Some((2024, 7, true)));
+        assert_eq!(parse_name("2015-25"), Some((2015, 25, false)));
+        assert_eq!(parse_name("2024-7"), None);
+        assert_eq!(parse_name(".keep"), None);
+    }
+}
@@ -0,0 +1,231 @@
+//! Runs days in parallel, each in a worker process of the runner. In-process solutions share
+//! stdout and end the process when they time out, so they can't share one. A run with a
+//! timeout uses workers even one at a time, so the runner outlives a day that times out.
+
+use crate::cli::RunArgs;
+use crate::config::{Config, OutputFormat};
+use crate::report::{DayReport, Reporter};
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use log::info;
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+
+/// Exit code of a worker whose case hit the timeout
+const TIMED_OUT: i32 = 124;
+
+/// What a worker printed, stdout and stderr interleaved, and the days it ran
+struct WorkerOutput {
+    log: Vec<u8>,
+    days: Vec<DayReport>,
+}
+
+/// Runs each day in a worker, `jobs` at a time. A day's output is printed once it and every day
+/// before it have finished, so it reads as a serial run would.
+pub fn run(
+    run: &RunArgs,
+    config: &Config,
+    days: &[(u32, u32)],
+    prebuilt: bool,
+    reporter: &mut Reporter,
+) -> Result<()> {
+    let jobs = config.job_count().min(days.len());
+    info!("running {} days with {jobs} workers", days.len());
+
+    let exe = std::env::current_exe().context("Failed to get current exe path")?;
+    let scratch = std::env::temp_dir().join(format!("aoc-jobs-{}", std::process::id()));
+    std::fs::create_dir_all(&scratch)
+        .with_context(|| format!("Failed to create {}", scratch.display()))?;
+
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+    let result = std::thread::scope(|scope| {
+        for _ in 0..jobs {
+            let sender = sender.clone();
+            let (next, exe, scratch) = (&next, &exe, &scratch);
+            scope.spawn(move || loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(&(year, day)) = days.get(index) else {
+                    break;
+                };
+                let output = run_worker(exe, scratch, run, config, year, day, prebuilt);
+                if sender.send((index, output)).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+
+        let mut finished = BTreeMap::new();
+        let mut next_to_print = 0;
+        for (index, output) in receiver {
+            finished.insert(index, output);
+            while let Some(output) = finished.remove(&next_to_print) {
+                let WorkerOutput { log, days } = output?;
+                // stdout is the report itself for JUnit and TAP
+                if config.format == OutputFormat::Human {
+                    std::io::stdout().write_all(&log)?;
+                } else {
+                    std::io::stderr().write_all(&log)?;
+                }
+                days.into_iter().for_each(|day| reporter.push_day(day));
+                next_to_print += 1;
+            }
+        }
+        anyhow::Ok(())
+    });
+
+    std::fs::remove_dir_all(&scratch).ok();
+    result
+}
+
+fn run_worker(
+    exe: &Path,
+    scratch: &Path,
+    run: &RunArgs,
+    config: &Config,
+    year: u32,
+    day: u32,
+    prebuilt: bool,
+) -> Result<WorkerOutput> {
+    let log_path = scratch.join(format!("{year}-{day:02}.log"));
+    let output_path = scratch.join(format!("{year}-{day:02}.json"));
+    let log = File::create(&log_path)
+        .with_context(|| format!("Failed to create {}", log_path.display()))?;
+
+    let status = Command::new(exe)
+        .args(worker_args(run, config, year, day, &output_path, prebuilt))
+        .stdin(Stdio::null())
+        .stdout(log.try_clone()?)
+        .stderr(log)
+        .status()
+        .context("Failed to start worker")?;
+
+    let log = std::fs::read(&log_path)
+        .with_context(|| format!("Failed to read {}", log_path.display()))?;
+    let days = Reporter::read_worker_output(&output_path).unwrap_or_else(|_| {
+        vec![DayReport {
+            year,
+            day,
+            cases: Vec::new(),
+            error: Some(match status.code() {
+                Some(TIMED_OUT) => "timed out".to_owned(),
+                _ => format!("worker {status} without reporting results"),
+            }),
+            source: None,
+            peak_rss_kb: None,
+            input_hash: None,
+        }]
+    });
+    Ok(WorkerOutput { log, days })
+}
+
+/// A `run` of just one day, with the parent's effective config passed as flags
+fn worker_args(
+    run: &RunArgs,
+    config: &Config,
+    year: u32,
+    day: u32,
+    output: &Path,
+    prebuilt: bool,
+) -> Vec<OsString> {
+    let colour = if config.colour_enabled() {
+        "always"
+    } else {
+        "never"
+    };
+    let mut args: Vec<OsString> = vec![
+        "run".into(),
+        "--year".into(),
+        year.to_string().into(),
+        "--day".into(),
+        day.to_string().into(),
+        "--manifest".into(),
+        run.manifest.clone().into(),
+        "--inputs".into(),
+        config.inputs.clone().into(),
+        "--profile".into(),
+        value_name(config.profile).into(),
+        "--backend".into(),
+        value_name(config.backend).into(),
+        "--format".into(),
+        value_name(config.format).into(),
+        "--colour".into(),
+        colour.into(),
+        "--jobs".into(),
+        "1".into(),
+        "--worker".into(),
+        PathBuf::from(output).into(),
+    ];
+
+    if let Some(part) = run.part_filter() {
+        args.extend(["--part".into(), (part as u8).to_string().into()]);
+    }
+    if let Some(case) = run.case {
+        args.extend(["--case".into(), case.to_string().into()]);
+    }
+    if run.only_solutions {
+        args.push("--only-solutions".into());
+    }
+    if let Some(implementation) = &run.implementation {
+        args.extend(["--impl".into(), implementation.into()]);
+    }
+    if let Some(timeout) = config.timeout {
+        args.extend(["--timeout".into(), timeout.to_string().into()]);
+    }
+    if prebuilt {
+        args.push("--no-build".into());
+    }
+    args
+}
+
+fn value_name(value: impl ValueEnum) -> String {
+    value
+        .to_possible_value()
+        .expect("no variants are skipped")
+        .get_name()
+        .to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::{Cli, CliCommand, Selection, Years};
+    use crate::config::Backend;
+    use clap::Parser;
+    use common::PartNumber;
+
+    #[test]
+    fn test_worker_args() {
+        let cli = Cli::try_parse_from(["aoc", "--day", "1-5", "--2", "--impl", "fast"]).unwrap();
+        let mut config = Config::defaults(Path::new("/ws")).unwrap();
+        config.timeout = Some(3);
+        config.jobs = 8;
+        config.backend = Backend::Wasm;
+
+        let args = worker_args(&cli.run, &config, 2024, 3, Path::new("/tmp/out.json"), true);
+        let worker = Cli::try_parse_from(std::iter::once("aoc".into()).chain(args)).unwrap();
+        let Some(CliCommand::Run(run)) = worker.command else {
+            panic!("expected run");
+        };
+        assert_eq!(run.year, Some(Years::Only(Selection(vec![2024]))));
+        assert_eq!(run.day, Some(Selection(vec![3])));
+        assert_eq!(run.part_filter(), Some(PartNumber::Part2));
+        assert_eq!(run.implementation.as_deref(), Some("fast"));
+        assert_eq!(run.worker, Some(PathBuf::from("/tmp/out.json")));
+        assert!(run.no_build);
+
+        let mut worker_config = Config::defaults(Path::new("/elsewhere")).unwrap();
+        worker.overrides.apply(&mut worker_config);
+        assert_eq!(worker_config.inputs, config.inputs);
+        assert_eq!(worker_config.timeout, Some(3));
+        assert_eq!(worker_config.jobs, 1);
+        assert_eq!(worker_config.backend, Backend::Wasm);
+    }
+}
@@ -0,0 +1,363 @@
+//! `aoc leaderboard`: standings and solve times of a private leaderboard
+
+use crate::calendar;
+use crate::cli::LeaderboardArgs;
+use crate::config::Config;
+use crate::{target_dir, NO_YEAR};
+use anyhow::{Context, Result};
+use common::strip_ansi;
+use log::info;
+use owo_colors::OwoColorize;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+const SESSION_VAR: &str = "AOC_SESSION";
+/// Advent of Code asks that leaderboards are fetched at most this often
+const CACHE_FOR: Duration = Duration::from_secs(15 * 60);
+const NAME_WIDTH: usize = 20;
+
+/// As adventofcode.com exports it
+#[derive(Debug, Deserialize)]
+struct Leaderboard {
+    event: String,
+    members: HashMap<String, Member>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Member {
+    id: u64,
+    name: Option<String>,
+    stars: u32,
+    /// Day to part to when its star was got
+    #[serde(default)]
+    completion_day_level: BTreeMap<u32, BTreeMap<u8, Star>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Star {
+    get_star_ts: u64,
+    /// Breaks ties between stars got in the same second
+    #[serde(default)]
+    star_index: u64,
+}
+
+impl Member {
+    fn name(&self) -> String {
+        self.name
+            .clone()
+            .unwrap_or_else(|| format!("(anonymous #{})", self.id))
+    }
+
+    fn star(&self, day: u32, part: u8) -> Option<&Star> {
+        self.completion_day_level.get(&day)?.get(&part)
+    }
+}
+
+pub fn run(config: &Config, args: LeaderboardArgs) -> Result<()> {
+    let json = match &args.from {
+        Some(path) => std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?,
+        None => fetch(config, args.year.or(config.year).context(NO_YEAR)?)?,
+    };
+    let leaderboard: Leaderboard =
+        serde_json::from_str(&json).context("Failed to parse the leaderboard")?;
+    let year = leaderboard
+        .event
+        .parse()
+        .with_context(|| format!("{:?} is not a year", leaderboard.event))?;
+
+    let text = render(&leaderboard, year, args.day);
+    if config.colour_enabled() {
+        print!("{text}");
+    } else {
+        print!("{}", strip_ansi(&text));
+    }
+    Ok(())
+}
+
+/// The leaderboard from `leaderboard` in the config, or the copy fetched in the last
+/// [`CACHE_FOR`]
+fn fetch(config: &Config, year: u32) -> Result<String> {
+    let cache = target_dir()?
+        .join("leaderboard")
+        .join(format!("{year}.json"));
+    let age = std::fs::metadata(&cache)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.elapsed().ok());
+    if let Some(age) = age.filter(|&age| age < CACHE_FOR) {
+        info!(
+            "using the leaderboard fetched {} ago",
+            calendar::countdown(age.as_secs())
+        );
+        return std::fs::read_to_string(&cache)
+            .with_context(|| format!("Failed to read {}", cache.display()));
+    }
+
+    let url = config
+        .leaderboard
+        .as_ref()
+        .context("set `leaderboard` in aoc.toml to its JSON URL, or pass --from")?
+        .replace("{year}", &year.to_string());
+    let session = session(config)?;
+
+    info!("fetching {url}");
+    // the session is written to curl's stdin so it isn't on its command line
+    let mut curl = Command::new("curl")
+        .args(["--silent", "--show-error", "--fail", "--header", "@-"])
+        .args(["--user-agent", "aoc runner leaderboard viewer"])
+        .arg(&url)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to run curl")?;
+    curl.stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(format!("Cookie: session={session}\n").as_bytes())?;
+    let output = curl.wait_with_output()?;
+    anyhow::ensure!(
+        output.status.success(),
+        "Failed to fetch {url}: {}",
+        String::from_utf8_lossy(&output.stderr).trim()
+    );
+    // an expired session is redirected to the login page
+    let json = String::from_utf8(output.stdout).context("leaderboard isn't UTF-8")?;
+    anyhow::ensure!(
+        json.trim_start().starts_with('{'),
+        "{url} didn't return JSON, is the session still valid?"
+    );
+
+    std::fs::create_dir_all(cache.parent().expect("cache is in a directory"))?;
+    std::fs::write(&cache, &json)
+        .with_context(|| format!("Failed to write {}", cache.display()))?;
+    Ok(json)
+}
+
+fn session(config: &Config) -> Result<String> {
+    if let Ok(session) = std::env::var(SESSION_VAR) {
+        return Ok(session.trim().to_owned());
+    }
+    let path = config
+        .session
+        .as_deref()
+        .context("no session cookie, set AOC_SESSION or `session` in aoc.toml")?;
+    read_session(path)
+}
+
+fn read_session(path: &Path) -> Result<String> {
+    std::fs::read_to_string(path)
+        .map(|session| session.trim().to_owned())
+        .with_context(|| {
+            format!(
+                "Failed to read the session cookie from {}, or set AOC_SESSION",
+                path.display()
+            )
+        })
+}
+
+/// Each member's local score after each day, scored as adventofcode.com does: every star is
+/// worth the number of members, less one for each member who got it first
+fn score_history(leaderboard: &Leaderboard, year: u32) -> HashMap<u64, Vec<u64>> {
+    let members = leaderboard.members.len() as u64;
+    let mut history: HashMap<u64, Vec<u64>> = HashMap::new();
+    let mut scores: HashMap<u64, u64> = HashMap::new();
+    for day in calendar::days(year) {
+        for part in 1..=2 {
+            let mut got = leaderboard
+                .members
+                .values()
+                .filter_map(|member| {
+                    let star = member.star(day, part)?;
+                    Some((star.get_star_ts, star.star_index, member.id))
+                })
+                .collect::<Vec<_>>();
+            got.sort_unstable();
+            for (rank, (_, _, id)) in got.into_iter().enumerate() {
+                *scores.entry(id).or_default() += members - rank as u64;
+            }
+        }
+        for member in leaderboard.members.values() {
+            let score = scores.get(&member.id).copied().unwrap_or_default();
+            history.entry(member.id).or_default().push(score);
+        }
+    }
+    history
+}
+
+/// Standings, then when each member got each day's stars, then how the scores grew
+fn render(leaderboard: &Leaderboard, year: u32, only_day: Option<u32>) -> String {
+    let history = score_history(leaderboard, year);
+    let score = |member: &Member| history[&member.id].last().copied().unwrap_or_default();
+    let mut members = leaderboard.members.values().collect::<Vec<_>>();
+    members.sort_by_key(|member| {
+        (
+            std::cmp::Reverse(score(member)),
+            std::cmp::Reverse(member.stars),
+            member.name(),
+        )
+    });
+    let name = |member: &Member| {
+        let name = member.name();
+        format!("{:<NAME_WIDTH$}", truncate(&name, NAME_WIDTH))
+    };
+
+    let mut out = format!(
+        "{}  {} members\n\n",
+        format!("{year} private leaderboard").bright_yellow().bold(),
+        members.len()
+    );
+    for (rank, member) in members.iter().enumerate() {
+        writeln!(
+            out,
+            "{:>4}) {:>5}  {:>2} {}  {}",
+            rank + 1,
+            score(member),
+            member.stars,
+            "*".bright_yellow(),
+            member.name()
+        )
+        .unwrap();
+    }
+
+    let days = calendar::days(year)
+        .filter(|&day| only_day.is_none_or(|only| only == day))
+        .filter(|&day| members.iter().any(|member| member.star(day, 1).is_some()))
+        .collect::<Vec<_>>();
+    for &day in &days {
+        let unlock = calendar::unlock(year, day);
+        writeln!(
+            out,
+            "\n{}  unlocked {}",
+            format!("Day {day}").bold(),
+            calendar::unlock_time(year, day)
+        )
+        .unwrap();
+        let mut solvers = members
+            .iter()
+            .filter_map(|member| {
+                let part1 = member.star(day, 1)?.get_star_ts;
+                let part2 = member.star(day, 2).map(|star| star.get_star_ts);
+                Some((part2.unwrap_or(u64::MAX), part1, member, part2))
+            })
+            .collect::<Vec<_>>();
+        solvers.sort_by_key(|&(part2, part1, member, _)| (part2, part1, member.id));
+        for (_, part1, member, part2) in solvers {
+            let (part2, delta) = match part2 {
+                Some(part2) => (
+                    solve_time(part2.saturating_sub(unlock)),
+                    format!("  +{}", solve_time(part2.saturating_sub(part1))),
+                ),
+                None => ("-".to_owned(), String::new()),
+            };
+            writeln!(
+                out,
+                "  {}  part 1 {:>12}  part 2 {:>12}{}",
+                name(member),
+                solve_time(part1.saturating_sub(unlock)),
+                part2,
+                delta.bright_black()
+            )
+            .unwrap();
+        }
+    }
+
+    if only_day.is_none() && !days.is_empty() {
+        let last = *days.last().expect("not empty") as usize;
+        write!(out, "\n{}\n  {:<NAME_WIDTH$}", "Local score".bold(), "").unwrap();
+        for day in 1..=last {
+            write!(out, " {day:>5}").unwrap();
+        }
+        out.push('\n');
+        for member in &members {
+            write!(out, "  {}", name(member)).unwrap();
+            for score in &history[&member.id][..last] {
+                write!(out, " {score:>5}").unwrap();
+            }
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// e.g. "0:05:12", or "2d 03:04:05" after a day
+fn solve_time(secs: u64) -> String {
+    let (days, hours, minutes, secs) = (secs / 86_400, secs / 3600 % 24, secs / 60 % 60, secs % 60);
+    if days > 0 {
+        format!("{days}d {hours:02}:{minutes:02}:{secs:02}")
+    } else {
+        format!("{hours}:{minutes:02}:{secs:02}")
+    }
+}
+
+fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_owned();
+    }
+    let mut truncated = text.chars().take(width - 1).collect::<String>();
+    truncated.push('…');
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2024-12-01 00:00 EST is 1733029200
+    const JSON: &str = r#"{
+        "event": "2024",
+        "owner_id": 1,
+        "members": {
+            "1": {"id": 1, "name": "Ada", "stars": 3, "local_score": 0, "global_score": 0,
+                  "last_star_ts": 0, "completion_day_level": {
+                "1": {"1": {"get_star_ts": 1733029512, "star_index": 10},
+                      "2": {"get_star_ts": 1733029900, "star_index": 12}},
+                "2": {"1": {"get_star_ts": 1733202000, "star_index": 20}}
+            }},
+            "2": {"id": 2, "name": null, "stars": 2, "local_score": 0, "global_score": 0,
+                  "last_star_ts": 0, "completion_day_level": {
+                "1": {"1": {"get_star_ts": 1733029400, "star_index": 5},
+                      "2": {"get_star_ts": 1733118000, "star_index": 30}}
+            }},
+            "3": {"id": 3, "name": "Grace", "stars": 0, "local_score": 0, "global_score": 0,
+                  "last_star_ts": 0, "completion_day_level": {}}
+        }
+    }"#;
+
+    #[test]
+    fn test_render() {
+        let leaderboard: Leaderboard = serde_json::from_str(JSON).unwrap();
+        let history = score_history(&leaderboard, 2024);
+        // day 1: #2 then Ada for part 1, Ada then #2 for part 2
+        assert_eq!(history[&1][..3], [5, 8, 8]);
+        assert_eq!(history[&2][..3], [5, 5, 5]);
+        assert_eq!(history[&3][..3], [0, 0, 0]);
+
+        let text = strip_ansi(&render(&leaderboard, 2024, None));
+        let lines = text.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], "2024 private leaderboard  3 members");
+        assert_eq!(lines[2], "   1)     8   3 *  Ada");
+        assert_eq!(lines[3], "   2)     5   2 *  (anonymous #2)");
+        assert_eq!(lines[4], "   3)     0   0 *  Grace");
+        assert_eq!(lines[6], "Day 1  unlocked 2024-12-01 00:00 EST");
+        assert_eq!(
+            lines[7],
+            "  Ada                   part 1      0:05:12  part 2      0:11:40  +0:06:28"
+        );
+        assert_eq!(
+            lines[8],
+            "  (anonymous #2)        part 1      0:03:20  part 2  1d 00:40:00  +1d 00:36:40"
+        );
+        assert!(text.contains("part 2            -\n"));
+        assert_eq!(lines[lines.len() - 3], "  Ada                      5     8");
+
+        let day = strip_ansi(&render(&leaderboard, 2024, Some(2)));
+        assert!(day.contains("Day 2") && !day.contains("Day 1") && !day.contains("Local score"));
+    }
+}
@@ -1,55 +1,462 @@
-use anyhow::{bail, Context, Result};
+use crate::answers::Answers;
+use crate::cli::{Cli, CliCommand, DayArgs, RunArgs, Selection, StarsArgs, Years};
+use crate::config::{workspace_root, Backend, Config, OutputFormat, Profile};
+use crate::discover::{discover, SolutionKind};
+use crate::external::Manifest;
+use crate::progress::ProgressDisplay;
+use crate::report::Reporter;
+use crate::viz::{Recording, VizOutput};
+use anyhow::{Context, Result};
+use clap::Parser;
 use common::itertools::Itertools;
-use common::{CaseEntrypointFn, PartNumber};
-use env_logger::Env;
+use common::report::ReportSinkFn;
+use common::{CaseEntrypointFn, ImplSelection, PartNumber, RunConfig, RunOptions};
+use env_logger::{Env, WriteStyle};
 use libloading::{Library, Symbol};
 use log::{error, info, warn};
-use std::env::args;
-use std::path::PathBuf;
+use std::collections::HashSet;
+use std::ffi::{c_void, OsStr};
+use std::path::{Path, PathBuf};
 use std::process::{Command, ExitCode};
+use std::time::Duration;
+
+mod answers;
+mod calendar;
+mod cases;
+mod cli;
+mod compare;
+mod config;
+mod discover;
+mod examples;
+mod external;
+mod fuzz;
+mod html;
+mod inputs;
+mod jobs;
+mod leaderboard;
+mod progress;
+mod repl;
+mod report;
+mod stars;
+mod viz;
+mod wasm;
+
+const NO_YEAR: &str = "--year is required, or set `year` in aoc.toml";
 
 #[derive(Debug, Clone)]
 pub struct Args {
-    /// 0 for all
     pub day: u32,
     pub year: u32,
     /// All parts if not set
     pub part: Option<PartNumber>,
     pub only_solutions: bool,
     pub case: Option<u32>,
+    /// Implementation name, or "all" to cross-check every implementation
+    pub implementation: Option<String>,
+    pub bench: bool,
+    pub viz: Option<VizOutput>,
+    /// Playback speed for --viz
+    pub fps: u32,
+    /// Keep only every nth recorded frame
+    pub viz_every: u32,
+    /// With any overriding flags applied
+    pub config: Config,
+    /// The day's input isn't the one its answers were recorded against
+    pub input_changed: bool,
+    /// Running in a worker process for the parent runner
+    pub worker: bool,
+}
+
+impl Args {
+    /// Per case for Rust solutions, whose watchdogs end the process, so only in a worker where
+    /// the parent carries on without it
+    pub fn case_timeout(&self) -> Option<Duration> {
+        self.worker.then(|| self.config.timeout()).flatten()
+    }
 }
 
 fn do_main() -> Result<()> {
-    env_logger::init_from_env(Env::default().default_filter_or("info"));
+    let cli = Cli::parse();
+    let loaded = Config::load().map(|(mut config, sources)| {
+        cli.overrides.apply(&mut config);
+        (config, sources)
+    });
+
+    // set up before anything can fail, so errors are logged
+    let write_style = match &loaded {
+        Ok((config, _)) if config.colour_enabled() => WriteStyle::Always,
+        Ok(_) => WriteStyle::Never,
+        Err(_) => WriteStyle::Auto,
+    };
+    env_logger::Builder::from_env(Env::default().default_filter_or("info"))
+        .write_style(write_style)
+        .init();
+
+    let (config, sources) = loaded?;
+    let root = workspace_root()?;
+
+    match cli.command {
+        None => run_days(&root, cli.run, config, false),
+        Some(CliCommand::Run(run)) => run_days(&root, run, config, false),
+        Some(CliCommand::Bench(run)) => run_days(&root, run, config, true),
+        Some(CliCommand::List { year }) => list(&root, year),
+        Some(CliCommand::New(day)) => new(&root, &config, day),
+        Some(CliCommand::Info(day)) => info(&root, &config, day),
+        Some(CliCommand::Config) => show_config(&config, &sources),
+        Some(CliCommand::Stars(stars)) => show_stars(&root, &config, stars),
+        Some(CliCommand::Fuzz(fuzz)) => fuzz::run(&root, &config, fuzz),
+        Some(CliCommand::Repl(repl)) => repl::run(&root, &config, repl),
+        Some(CliCommand::Examples(examples)) => examples::run(&root, &config, examples),
+        Some(CliCommand::VerifyInputs { year }) => {
+            inputs::verify(&config, &Answers::load(&config.answers)?, year.as_ref())
+        }
+        Some(CliCommand::Calendar(calendar)) => calendar::run(&config, calendar),
+        Some(CliCommand::Leaderboard(leaderboard)) => leaderboard::run(&config, leaderboard),
+        Some(CliCommand::Inputs { command }) => inputs::run(&config, command),
+    }
+}
+
+fn run_days(root: &Path, run: RunArgs, config: Config, bench: bool) -> Result<()> {
+    if let Some(rev) = &run.compare {
+        return compare::run(root, &run, &config, rev);
+    }
+    let part = run.part_filter();
+    let manifest = Manifest::load(&root.join(&run.manifest))?;
+    let days = selected_days(root, &run, &config, manifest.as_ref())?;
 
-    let mut args = Args::parse()?;
+    let mut args = Args {
+        day: 0,
+        year: 0,
+        part,
+        only_solutions: run.only_solutions,
+        case: run.case,
+        implementation: run.implementation.clone(),
+        bench: run.bench || bench,
+        viz: run.viz.clone(),
+        fps: run.fps,
+        viz_every: run.viz_every,
+        config,
+        input_changed: false,
+        worker: run.worker.is_some(),
+    };
 
     info!("lets go: {args:?}");
 
-    let days = if args.day == 0 {
-        (1..=25).collect_vec()
-    } else {
-        vec![args.day]
+    let mut reporter = match &run.worker {
+        Some(output) => Reporter::worker(args.config.format, output.clone()),
+        None => Reporter::new(args.config.format, run.html.is_some()),
     };
 
-    for day in days {
+    let rust_days = days
+        .iter()
+        .copied()
+        .filter(|&(year, day)| {
+            manifest
+                .as_ref()
+                .is_none_or(|m| m.find(year, day).is_none())
+                && discover::crate_dir(root, year, day).exists()
+        })
+        .collect_vec();
+    // built together up front, or if that fails, day by day so the error lands on the day it
+    // belongs to
+    let prebuilt = run.no_build
+        || rust_days.len() > 1
+            && ensure_built_for_backend(&args.config, &rust_days)
+                .inspect_err(|e| warn!("{e:#}, building each day separately"))
+                .is_ok();
+
+    let parallel = args.config.job_count() > 1 && days.len() > 1;
+    // a timed out case ends its process, so with a timeout each day gets a worker of its own
+    let isolate = args.config.timeout().is_some();
+    if run.worker.is_none() && (parallel || isolate) {
+        if args.bench || args.viz.is_some() {
+            if parallel && args.bench {
+                info!("benchmarking one day at a time so timings aren't skewed");
+            } else if parallel {
+                info!("visualising one day at a time");
+            }
+            if isolate {
+                warn!(
+                    "--timeout only applies to external solutions when benchmarking or visualising"
+                );
+            }
+        } else {
+            jobs::run(&run, &args.config, &days, prebuilt, &mut reporter)?;
+            return finish_run(&run, &args.config, &reporter);
+        }
+    }
+
+    let answers = Answers::load(&args.config.answers)?;
+    for (year, day) in days {
+        args.year = year;
         args.day = day;
-        let input = format!("inputs/{}-{:02}", args.year, args.day);
-        info!("reading input from {input}");
+        info!(
+            "reading input from {}",
+            args.config.input_path(year, day).display()
+        );
+        let input = inputs::read(&args.config, year, day);
+        let input_hash = input.as_deref().ok().map(inputs::fingerprint);
+        args.input_changed = input_hash
+            .as_deref()
+            .is_some_and(|hash| answers.input_changed(year, day, hash));
+        if args.input_changed {
+            warn!("{year} day {day} input changed since answers were recorded");
+        }
+
+        let external = manifest
+            .as_ref()
+            .and_then(|manifest| Some((manifest, manifest.find(year, day)?)));
+        let source = match external {
+            Some((manifest, solution)) => solution.source(manifest.dir()),
+            None => Some(discover::crate_dir(root, year, day).join("src/lib.rs")),
+        };
+        reporter.begin_day(year, day, external.is_none(), source, input_hash)?;
+        let report_sink = reporter.sink();
 
         let do_it = || {
-            let input = std::fs::read_to_string(&input)
-                .with_context(|| format!("Failed to read input from {input}"))?;
+            let input = input?;
 
-            ensure_solution_built(&args).context("Failed to ensure solution is built")?;
+            if let Some((manifest, solution)) = external {
+                info!("running external solution {:?}", solution.command);
+                let passed = solution.run(
+                    manifest.dir(),
+                    &input,
+                    &run_config(&args, report_sink),
+                    args.config.timeout(),
+                )?;
+                log_outcome(passed);
+                return Ok(());
+            }
+
+            if !prebuilt || !rust_days.contains(&(year, day)) {
+                ensure_built_for_backend(&args.config, &[(year, day)])
+                    .context("Failed to ensure solution is built")?;
+            }
 
-            run_solution(args.clone(), input.trim_end())
+            match args.config.backend {
+                Backend::Native => run_solution(args.clone(), &input, report_sink),
+                Backend::Wasm => wasm::run_solution(&args, &input, report_sink),
+            }
         };
 
-        if let Err(e) = do_it() {
-            error!("failed to run for day {day}: {e:#}");
+        let result = do_it();
+        if let Err(e) = &result {
+            error!("failed to run for {year} day {day}: {e:#}");
+        }
+        reporter.end_day(result.err().map(|e| format!("{e:#}")));
+    }
+
+    finish_run(&run, &args.config, &reporter)
+}
+
+/// Years and days to run, in order. `--year all` is every solution, rather than every day.
+fn selected_days(
+    root: &Path,
+    run: &RunArgs,
+    config: &Config,
+    manifest: Option<&Manifest>,
+) -> Result<Vec<(u32, u32)>> {
+    let in_days = |day: &u32| {
+        run.day
+            .as_ref()
+            .is_none_or(|Selection(days)| days.contains(day))
+    };
+    let years = match &run.year {
+        Some(Years::All) => {
+            let days = discover(root, manifest)?
+                .into_iter()
+                .map(|solution| (solution.year, solution.day))
+                .filter(|(_, day)| in_days(day))
+                .collect_vec();
+            anyhow::ensure!(!days.is_empty(), "no solutions for the selected days");
+            return Ok(days);
+        }
+        Some(Years::Only(Selection(years))) => years.clone(),
+        None => vec![config.year.context(NO_YEAR)?],
+    };
+    let now = calendar::now();
+    let days = years
+        .into_iter()
+        .flat_map(|year| calendar::days(year).map(move |day| (year, day)))
+        .filter(|(year, day)| in_days(day) && calendar::is_unlocked(*year, *day, now))
+        .collect_vec();
+    anyhow::ensure!(
+        !days.is_empty(),
+        "none of the selected days are in the calendar or unlocked yet"
+    );
+    Ok(days)
+}
+
+/// Renders the report and records answers, or in a worker, hands the days to the parent
+fn finish_run(run: &RunArgs, config: &Config, reporter: &Reporter) -> Result<()> {
+    reporter.finish()?;
+    if run.worker.is_some() {
+        return Ok(());
+    }
+    if config.format == OutputFormat::Human && reporter.days().len() > 1 {
+        print!(
+            "{}",
+            report::summary(reporter.days(), config.colour_enabled())
+        );
+    }
+
+    if let Some(path) = &run.html {
+        let path = std::path::absolute(path).context("Failed to resolve --html path")?;
+        let html = html::render(reporter.days(), &path, run.redact);
+        std::fs::write(&path, html)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        info!("wrote report to {}", path.display());
+    }
+
+    let mut answers = Answers::load(&config.answers)?;
+    let mut changed = false;
+    for day in reporter.days() {
+        changed |= answers.record(day);
+    }
+    if changed {
+        answers.save(&config.answers)?;
+    }
+    Ok(())
+}
+
+fn list(root: &Path, years: Option<Selection>) -> Result<()> {
+    let manifest = Manifest::load(&root.join(external::DEFAULT_MANIFEST))?;
+    let solutions = discover(root, manifest.as_ref())?;
+
+    for (year, solutions) in &solutions
+        .iter()
+        .filter(|s| {
+            years
+                .as_ref()
+                .is_none_or(|Selection(years)| years.contains(&s.year))
+        })
+        .chunk_by(|s| s.year)
+    {
+        let solutions = solutions.collect_vec();
+        println!("{year} ({} solutions)", solutions.len());
+        for solution in solutions {
+            let kind = match &solution.kind {
+                SolutionKind::Rust { crate_dir } => crate_dir
+                    .strip_prefix(root)
+                    .unwrap_or(crate_dir)
+                    .display()
+                    .to_string(),
+                SolutionKind::External { command } => command.join(" "),
+            };
+            println!("  {:02}  {kind}", solution.day);
         }
     }
+
+    Ok(())
+}
+
+fn show_stars(root: &Path, config: &Config, args: StarsArgs) -> Result<()> {
+    let manifest = Manifest::load(&root.join(external::DEFAULT_MANIFEST))?;
+    let solutions: HashSet<_> = discover(root, manifest.as_ref())?
+        .into_iter()
+        .map(|solution| (solution.year, solution.day))
+        .collect();
+    let answers = Answers::load(&config.answers)?;
+
+    let years = match args.year {
+        Some(Selection(years)) => years,
+        None => solutions
+            .iter()
+            .map(|&(year, _)| year)
+            .chain(answers.years())
+            .sorted()
+            .dedup()
+            .collect(),
+    };
+
+    for &year in &years {
+        println!(
+            "{}",
+            stars::calendar(year, &answers, &solutions, config.colour_enabled())
+        );
+    }
+
+    if let Some(path) = args.markdown {
+        stars::update_markdown(&path, &stars::markdown(&years, &answers, &solutions))?;
+        info!("updated progress table in {}", path.display());
+    }
+    Ok(())
+}
+
+fn new(root: &Path, config: &Config, day: DayArgs) -> Result<()> {
+    let (year, day) = day.resolve(config)?;
+    let dir = discover::new_solution(root, year, day)?;
+    info!("created {}", dir.display());
+
+    let now = calendar::now();
+    let input = config.input_path(year, day);
+    if !calendar::is_unlocked(year, day, now) {
+        warn!(
+            "the puzzle unlocks in {}, download its input to {} then",
+            calendar::countdown(calendar::unlock(year, day) - now),
+            input.display()
+        );
+    } else if !input.exists() && !config.encrypted_input_path(year, day).exists() {
+        warn!("no input yet, download it to {}", input.display());
+    }
+    Ok(())
+}
+
+fn info(root: &Path, config: &Config, day: DayArgs) -> Result<()> {
+    let (year, day) = day.resolve(config)?;
+    let manifest = Manifest::load(&root.join(external::DEFAULT_MANIFEST))?;
+
+    println!("{year}-{day:02}");
+    let unlocked = if calendar::is_unlocked(year, day, calendar::now()) {
+        ""
+    } else {
+        " (locked)"
+    };
+    println!("  unlocks   {}{unlocked}", calendar::unlock_time(year, day));
+    match manifest
+        .as_ref()
+        .and_then(|m| Some((m, m.find(year, day)?)))
+    {
+        Some((manifest, solution)) => {
+            println!(
+                "  solution  external: {} (in {})",
+                solution.command.join(" "),
+                manifest.dir().display()
+            );
+        }
+        None => {
+            let crate_dir = discover::crate_dir(root, year, day);
+            if !crate_dir.exists() {
+                println!("  solution  none, create it with `aoc new --year {year} --day {day}`");
+            } else {
+                println!("  solution  {}", crate_dir.display());
+                println!(
+                    "  source    {}",
+                    discover::source_link(root, year, day).display()
+                );
+                let (kind, lib) = match config.backend {
+                    Backend::Native => ("library", library_path(config, year, day)?),
+                    Backend::Wasm => ("module ", wasm::module_path(config, year, day)?),
+                };
+                let built = if lib.exists() { "" } else { " (not built)" };
+                println!("  {kind}   {}{built}", lib.display());
+            }
+        }
+    }
+
+    let input = config.input_path(year, day);
+    let encrypted = config.encrypted_input_path(year, day);
+    match std::fs::read_to_string(&input) {
+        Ok(text) => println!(
+            "  input     {} ({} lines, {} bytes)",
+            input.display(),
+            text.lines().count(),
+            text.len()
+        ),
+        Err(_) if encrypted.exists() => println!("  input     {} (encrypted)", encrypted.display()),
+        Err(_) => println!("  input     {} (missing)", input.display()),
+    }
+
     Ok(())
 }
 
@@ -63,38 +470,96 @@ fn main() -> ExitCode {
     }
 }
 
-fn solution_format(args: &Args) -> String {
-    format!("aoc_{}_{:02}", args.year, args.day,)
+fn solution_format(year: u32, day: u32) -> String {
+    format!("aoc_{year}_{day:02}")
 }
 
-fn ensure_solution_built(args: &Args) -> Result<()> {
-    let project_dir =
-        std::env::var("CARGO_MANIFEST_DIR").context("Failed to get CARGO_MANIFEST_DIR")?;
+fn library_path(config: &Config, year: u32, day: u32) -> Result<PathBuf> {
+    Ok(library_in(&target_dir()?, config, year, day))
+}
 
-    let profile_arg = match std::env::current_exe()
+/// Where cargo puts a solution library built into `target_dir`
+fn library_in(target_dir: &Path, config: &Config, year: u32, day: u32) -> PathBuf {
+    target_dir
+        .join(config.profile.dir_name())
+        .join(format!("lib{}.so", solution_format(year, day)))
+}
+
+/// The target directory the runner itself was built into
+fn target_dir() -> Result<PathBuf> {
+    std::env::current_exe()
         .context("Failed to get current exe path")?
         .parent()
-        .context("Failed to get parent of current exe path")?
-        .file_name()
-        .context("Failed to get file name of parent of current exe path")?
-        .to_str()
-        .context("Failed to convert file name of parent of current exe path to str")?
-    {
-        "debug" => None,
-        "release" => Some("--release"),
-        _ => {
-            return Err(anyhow::anyhow!(
-                "did not find debug or release in current binary dir"
-            ))
-        }
+        .and_then(|profile_dir| profile_dir.parent())
+        .map(Path::to_owned)
+        .context("Failed to get target dir from current exe path")
+}
+
+fn ensure_solution_built(config: &Config, year: u32, day: u32) -> Result<()> {
+    ensure_solutions_built(config, &[(year, day)])
+}
+
+/// Builds the solution crates of several days with one cargo invocation
+fn ensure_solutions_built(config: &Config, days: &[(u32, u32)]) -> Result<()> {
+    cargo_build(&runner_dir()?, None, None, config, days)
+}
+
+/// Builds for whichever backend `run` uses
+fn ensure_built_for_backend(config: &Config, days: &[(u32, u32)]) -> Result<()> {
+    match config.backend {
+        Backend::Native => ensure_solutions_built(config, days),
+        Backend::Wasm => wasm::ensure_built(config, days),
+    }
+}
+
+/// The runner crate's directory, in the workspace being run
+fn runner_dir() -> Result<PathBuf> {
+    std::env::var_os("CARGO_MANIFEST_DIR")
+        .map(PathBuf::from)
+        .context("Failed to get CARGO_MANIFEST_DIR")
+}
+
+/// `cargo build` of the days' solution crates in the workspace at `dir`, for the host unless
+/// a target is given
+fn cargo_build(
+    dir: &Path,
+    target_dir: Option<&Path>,
+    target: Option<&str>,
+    config: &Config,
+    days: &[(u32, u32)],
+) -> Result<()> {
+    if days.is_empty() {
+        return Ok(());
+    }
+
+    let profile_arg = match config.profile {
+        Profile::Debug => None,
+        Profile::Release => Some("--release"),
     };
 
-    info!("running cargo build on solution crate");
+    info!("running cargo build on {} solution crates", days.len());
     let output = Command::new("cargo")
-        .current_dir(project_dir)
+        .current_dir(dir)
         .arg("build")
-        .args(["--package", &solution_format(args).replace('_', "-")])
+        .args(days.iter().flat_map(|&(year, day)| {
+            [
+                "--package".to_owned(),
+                solution_format(year, day).replace('_', "-"),
+            ]
+        }))
         .args(profile_arg)
+        .args(
+            target
+                .map(|target| ["--target", target])
+                .into_iter()
+                .flatten(),
+        )
+        .args(
+            target_dir
+                .map(|dir| [OsStr::new("--target-dir"), dir.as_os_str()])
+                .into_iter()
+                .flatten(),
+        )
         .output()
         .context("Failed to execute cargo build")?;
 
@@ -106,14 +571,8 @@ fn ensure_solution_built(args: &Args) -> Result<()> {
     Ok(())
 }
 
-fn run_solution(args: Args, input: &str) -> Result<()> {
-    let lib_path = PathBuf::from(
-        std::env::current_exe()
-            .context("Failed to get current exe path")?
-            .parent()
-            .context("Failed to get parent of current exe path")?,
-    )
-    .join(format!("lib{}.so", solution_format(&args)));
+fn run_solution(args: Args, input: &str, report_sink: (ReportSinkFn, *mut c_void)) -> Result<()> {
+    let lib_path = library_path(&args.config, args.year, args.day)?;
 
     info!("loading solution from {}", lib_path.display());
 
@@ -125,21 +584,37 @@ fn run_solution(args: Args, input: &str) -> Result<()> {
                 .get(b"run_cases_entrypoint")
                 .context("Failed to load run_cases_entrypoint symbol")?;
 
-            let part_filter = args.part.map(|p| p as u8).unwrap_or(0);
-            let case_filter = args.case.unwrap_or(0);
+            let implementation = args.implementation.as_deref().unwrap_or_default();
+            let mut recording = args.viz.as_ref().map(|_| Recording::default());
+            let mut progress = ProgressDisplay::new();
+            let options = RunOptions {
+                input_ptr: input.as_ptr(),
+                input_len_bytes: input.len(),
+                part_filter: args.part.map(|p| p as u8).unwrap_or(0),
+                case_filter: args.case.unwrap_or(0),
+                solutions_only: args.only_solutions,
+                impl_ptr: implementation.as_ptr(),
+                impl_len_bytes: implementation.len(),
+                bench: args.bench,
+                viz_sink: recording.as_ref().map(|_| Recording::sink as _),
+                viz_ctx: recording
+                    .as_mut()
+                    .map_or(std::ptr::null_mut(), Recording::as_ctx),
+                viz_every: args.viz_every,
+                progress_sink: Some(ProgressDisplay::sink),
+                progress_ctx: progress.as_ctx(),
+                report_sink: Some(report_sink.0),
+                report_ctx: report_sink.1,
+                colour: args.config.colour_enabled(),
+                timeout_ms: args.case_timeout().map_or(0, |t| t.as_millis() as u64),
+                input_changed: args.input_changed,
+            };
 
             info!("calling run_cases entrypoint");
-            let res = func(
-                input.as_ptr(),
-                input.len(),
-                part_filter,
-                case_filter,
-                args.only_solutions,
-            );
-            if res {
-                info!("all cases passed");
-            } else {
-                warn!("some cases failed");
+            log_outcome(func(&options));
+
+            if let (Some(recording), Some(output)) = (&recording, &args.viz) {
+                recording.output(output, args.fps)?;
             }
 
             Ok(())
@@ -159,57 +634,43 @@ fn run_solution(args: Args, input: &str) -> Result<()> {
     }
 }
 
-impl Args {
-    pub fn parse() -> Result<Self> {
-        let mut args = args().skip(1);
-        let mut day = 0;
-        let mut year = None;
-        let mut part = None;
-        let mut only_solutions = false;
-        let mut case = None;
-
-        while let Some(arg) = args.next() {
-            match arg.as_str() {
-                "--day" => {
-                    day = args
-                        .next()
-                        .context("--day requires a number")?
-                        .parse()
-                        .context("day must be a valid number")?;
-                }
-                "--year" => {
-                    year = Some(
-                        args.next()
-                            .context("--year requires a number")?
-                            .parse()
-                            .context("year must be a valid number")?,
-                    );
-                }
-                "--1" => {
-                    part = Some(PartNumber::Part1);
-                }
-                "--2" => {
-                    part = Some(PartNumber::Part2);
-                }
-                "--case" => {
-                    case = Some(
-                        args.next()
-                            .context("--case requires a number")?
-                            .parse()
-                            .context("case must be a valid number")?,
-                    );
-                }
-                "--only-solutions" => only_solutions = true,
-                _ => bail!("unknown argument"),
-            }
-        }
+fn log_outcome(passed: bool) {
+    if passed {
+        info!("all cases passed");
+    } else {
+        warn!("some cases failed");
+    }
+}
 
-        Ok(Args {
-            day,
-            year: year.context("--year is required")?,
-            part,
-            only_solutions,
-            case,
-        })
+fn show_config(config: &Config, sources: &[PathBuf]) -> Result<()> {
+    if sources.is_empty() {
+        println!("# no config files found, using defaults");
+    }
+    for source in sources {
+        println!("# from {}", source.display());
+    }
+    print!(
+        "{}",
+        toml::to_string(config).context("Failed to serialise config")?
+    );
+    Ok(())
+}
+
+/// The [`RunOptions`] equivalent for solutions run in-process
+fn run_config(args: &Args, report_sink: (ReportSinkFn, *mut c_void)) -> RunConfig {
+    RunConfig {
+        part_filter: args.part,
+        case_filter: args.case.unwrap_or(0),
+        solutions_only: args.only_solutions,
+        implementation: ImplSelection::parse(args.implementation.as_deref().unwrap_or_default()),
+        bench: args.bench,
+        viz_sink: None,
+        viz_every: 1,
+        progress_sink: None,
+        report_sink: Some(report_sink),
+        colour: args.config.colour_enabled(),
+        // external programs are killed instead
+        timeout: None,
+        input_changed: args.input_changed,
     }
 }
@@ -0,0 +1,130 @@
+use common::progress::ProgressUpdate;
+use log::info;
+use std::ffi::c_void;
+use std::io::{IsTerminal, Write};
+use std::time::{Duration, Instant};
+
+const BAR_WIDTH: usize = 30;
+
+/// How often progress is logged when stderr isn't a terminal
+const LOG_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Renders progress updates from the solution, as a live bar on a terminal or log lines otherwise
+pub struct ProgressDisplay {
+    is_tty: bool,
+    /// When the current bar got its first update
+    started: Option<Instant>,
+    last_log: Instant,
+}
+
+impl ProgressDisplay {
+    pub fn new() -> Self {
+        Self {
+            is_tty: std::io::stderr().is_terminal(),
+            started: None,
+            last_log: Instant::now(),
+        }
+    }
+
+    pub fn as_ctx(&mut self) -> *mut c_void {
+        self as *mut Self as *mut c_void
+    }
+
+    /// # Safety
+    /// `ctx` must come from [`ProgressDisplay::as_ctx`] and `update` be valid for the call.
+    pub unsafe extern "C" fn sink(ctx: *mut c_void, update: *const ProgressUpdate) {
+        let display = &mut *(ctx as *mut Self);
+        let update = &*update;
+        let message = String::from_utf8_lossy(std::slice::from_raw_parts(
+            update.message_ptr,
+            update.message_len,
+        ));
+
+        display.update(update, &message);
+    }
+
+    fn update(&mut self, update: &ProgressUpdate, message: &str) {
+        let started = *self.started.get_or_insert_with(Instant::now);
+        let elapsed = started.elapsed();
+        let line = format_line(update, message, elapsed);
+
+        if self.is_tty {
+            let mut stderr = std::io::stderr().lock();
+            let _ = write!(stderr, "\r\x1b[2K{line}");
+            if update.finished {
+                let _ = writeln!(stderr);
+            }
+            let _ = stderr.flush();
+        } else if update.finished || self.last_log.elapsed() >= LOG_INTERVAL {
+            info!("progress: {line}");
+            self.last_log = Instant::now();
+        }
+
+        if update.finished {
+            self.started = None;
+        }
+    }
+}
+
+fn format_line(update: &ProgressUpdate, message: &str, elapsed: Duration) -> String {
+    let rate = update.current as f64 / elapsed.as_secs_f64().max(f64::EPSILON);
+    let rate = format!("{}/s", human_count(rate));
+    let elapsed = format!("{:.1?}", elapsed);
+
+    if update.total == 0 {
+        return format!(
+            "{message} {} {rate} [{elapsed}]",
+            human_count(update.current as f64)
+        );
+    }
+
+    let fraction = (update.current as f64 / update.total as f64).clamp(0.0, 1.0);
+    let filled = (fraction * BAR_WIDTH as f64) as usize;
+    let bar = format!("{}{}", "#".repeat(filled), ".".repeat(BAR_WIDTH - filled));
+
+    format!(
+        "{message} [{bar}] {:>5.1}% {}/{} {rate} [{elapsed}]",
+        fraction * 100.0,
+        human_count(update.current as f64),
+        human_count(update.total as f64),
+    )
+}
+
+fn human_count(n: f64) -> String {
+    match n {
+        n if n >= 1e9 => format!("{:.1}G", n / 1e9),
+        n if n >= 1e6 => format!("{:.1}M", n / 1e6),
+        n if n >= 1e3 => format!("{:.1}k", n / 1e3),
+        n => format!("{n:.0}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn update(current: u64, total: u64) -> ProgressUpdate {
+        ProgressUpdate {
+            current,
+            total,
+            message_ptr: std::ptr::null(),
+            message_len: 0,
+            finished: false,
+        }
+    }
+
+    #[test]
+    fn test_format_line() {
+        let elapsed = Duration::from_secs(2);
+        assert_eq!(
+            format_line(&update(3_000_000, 0), "hashing", elapsed),
+            "hashing 3.0M 1.5M/s [2.0s]"
+        );
+        assert_eq!(
+            format_line(&update(250, 1000), "trying", elapsed),
+            "trying [#######.......................]  25.0% 250/1.0k 125/s [2.0s]"
+        );
+        assert!(format_line(&update(2000, 1000), "over", elapsed)
+            .starts_with(&format!("over [{}] 100.0%", "#".repeat(BAR_WIDTH))));
+    }
+}
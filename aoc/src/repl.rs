@@ -0,0 +1,403 @@
+//! `aoc repl`: solves pasted inputs with a loaded solution, saving them as example cases
+
+use crate::cli::ReplArgs;
+use crate::config::Config;
+use crate::{cases, discover, ensure_solution_built, library_path};
+use anyhow::{Context, Result};
+use common::report::Outcome;
+use common::snippet::{
+    AbiVersionFn, SnippetEntrypointFn, SnippetOptions, SnippetReport, ABI_VERSION,
+};
+use common::{strip_ansi, PartNumber};
+use libloading::{Library, Symbol};
+use log::{info, warn};
+use owo_colors::OwoColorize;
+use std::ffi::c_void;
+use std::io::{BufRead, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Prefix of the consts saved inputs are added as
+const EXAMPLE_PREFIX: &str = "EXAMPLE";
+/// Ends a pasted input
+const END_OF_INPUT: &str = ".";
+
+const HELP: &str = "\
+Paste an input and end it with a line holding just `.` to solve it. Commands:
+  :part 1|2        part to solve, 1 to start with
+  :impl [NAME]     implementation to solve with, the default if no name
+  :run             solve the last input again
+  :show            print the last input
+  :save [ANSWER]   add the last input as an example case, with ANSWER or the last answer
+  :build           rebuild the solution, which is also reloaded whenever it's rebuilt elsewhere
+  :help
+  :quit";
+
+#[derive(Debug, PartialEq, Eq)]
+enum Command {
+    Part(PartNumber),
+    Impl(Option<String>),
+    Run,
+    Show,
+    Save(Option<String>),
+    Build,
+    Help,
+    Quit,
+}
+
+impl Command {
+    fn parse(line: &str) -> Result<Self, String> {
+        let (name, arg) = line
+            .trim()
+            .split_once(' ')
+            .map_or((line.trim(), None), |(name, arg)| {
+                (
+                    name,
+                    Some(arg.trim().to_owned()).filter(|arg| !arg.is_empty()),
+                )
+            });
+        match (name, arg.as_deref()) {
+            (":part", Some("1")) => Ok(Self::Part(PartNumber::Part1)),
+            (":part", Some("2")) => Ok(Self::Part(PartNumber::Part2)),
+            (":part", _) => Err("usage: :part 1|2".to_owned()),
+            (":impl", _) => Ok(Self::Impl(arg)),
+            (":run", None) => Ok(Self::Run),
+            (":show", None) => Ok(Self::Show),
+            (":save", _) => Ok(Self::Save(arg)),
+            (":build", None) => Ok(Self::Build),
+            (":help", None) => Ok(Self::Help),
+            (":quit" | ":q", None) => Ok(Self::Quit),
+            _ => Err(format!("unknown command {line:?}, see :help")),
+        }
+    }
+}
+
+/// What a line typed at the prompt amounts to
+#[derive(Debug, PartialEq, Eq)]
+enum Entry {
+    Command(Result<Command, String>),
+    /// A complete pasted input
+    Input(String),
+}
+
+/// Collects pasted lines until [`END_OF_INPUT`]
+#[derive(Debug, Default)]
+struct Reader {
+    pasting: Vec<String>,
+}
+
+impl Reader {
+    fn line(&mut self, line: &str) -> Option<Entry> {
+        if self.pasting.is_empty() && line.starts_with(':') {
+            return Some(Entry::Command(Command::parse(line)));
+        }
+        if line == END_OF_INPUT {
+            return Some(Entry::Input(std::mem::take(&mut self.pasting).join("\n")));
+        }
+        self.pasting.push(line.to_owned());
+        None
+    }
+
+    /// At end of file, anything pasted so far is the last input
+    fn finish(&mut self) -> Option<Entry> {
+        (!self.pasting.is_empty())
+            .then(|| Entry::Input(std::mem::take(&mut self.pasting).join("\n")))
+    }
+}
+
+/// The solution library, loaded from a copy so a rebuilt one is loaded fresh rather than the
+/// already open one being handed back
+struct Loaded {
+    library: Library,
+    copy: PathBuf,
+    built: SystemTime,
+}
+
+impl Loaded {
+    fn load(source: &Path, generation: u32) -> Result<Self> {
+        let built = modified(source)?;
+        let copy =
+            std::env::temp_dir().join(format!("aoc-repl-{}-{generation}.so", std::process::id()));
+        std::fs::copy(source, &copy)
+            .with_context(|| format!("Failed to copy {}", source.display()))?;
+        let library = unsafe { Library::new(&copy) }.context("Failed to load solution library")?;
+        Ok(Self {
+            library,
+            copy,
+            built,
+        })
+    }
+}
+
+impl Drop for Loaded {
+    fn drop(&mut self) {
+        std::fs::remove_file(&self.copy).ok();
+    }
+}
+
+struct Session<'a> {
+    config: &'a Config,
+    year: u32,
+    day: u32,
+    source: PathBuf,
+    library_path: PathBuf,
+    loaded: Loaded,
+    generation: u32,
+    part: PartNumber,
+    implementation: Option<String>,
+    last_input: Option<String>,
+    last_report: Option<SnippetReport>,
+}
+
+pub fn run(root: &Path, config: &Config, args: ReplArgs) -> Result<()> {
+    let (year, day) = args.day.resolve(config)?;
+    let source = discover::crate_dir(root, year, day).join("src/lib.rs");
+    anyhow::ensure!(
+        source.exists(),
+        "no solution crate for {year} day {day}, the REPL only loads Rust solutions"
+    );
+
+    ensure_solution_built(config, year, day).context("Failed to ensure solution is built")?;
+    let library_path = library_path(config, year, day)?;
+    let mut session = Session {
+        config,
+        year,
+        day,
+        source,
+        loaded: Loaded::load(&library_path, 0)?,
+        library_path,
+        generation: 0,
+        part: PartNumber::Part1,
+        implementation: args.implementation,
+        last_input: None,
+        last_report: None,
+    };
+
+    let interactive = std::io::stdin().is_terminal();
+    if interactive {
+        println!("{year} day {day}. {HELP}");
+    }
+    let mut reader = Reader::default();
+    let mut lines = std::io::stdin().lock().lines();
+    loop {
+        if interactive && reader.pasting.is_empty() {
+            print!("part {}> ", session.part as u8);
+            std::io::stdout().flush().ok();
+        }
+        let entry = match lines.next() {
+            Some(line) => reader.line(&line.context("Failed to read stdin")?),
+            None => match reader.finish() {
+                None => break,
+                entry => entry,
+            },
+        };
+
+        let result = match entry {
+            Some(Entry::Command(Ok(Command::Quit))) => break,
+            Some(Entry::Command(Ok(command))) => session.command(command),
+            Some(Entry::Command(Err(usage))) => {
+                session.say(usage.yellow().to_string());
+                Ok(())
+            }
+            Some(Entry::Input(input)) => {
+                session.last_input = Some(input);
+                session.solve()
+            }
+            None => Ok(()),
+        };
+        if let Err(e) = result {
+            session.say(format!("{e:#}").red().to_string());
+        }
+    }
+    Ok(())
+}
+
+impl Session<'_> {
+    fn say(&self, text: String) {
+        if self.config.colour_enabled() {
+            println!("{text}");
+        } else {
+            println!("{}", strip_ansi(&text));
+        }
+    }
+
+    fn command(&mut self, command: Command) -> Result<()> {
+        match command {
+            Command::Part(part) => self.part = part,
+            Command::Impl(name) => self.implementation = name,
+            Command::Run => self.solve()?,
+            Command::Show => match &self.last_input {
+                Some(input) => println!("{input}"),
+                None => anyhow::bail!("nothing pasted yet"),
+            },
+            Command::Save(answer) => self.save(answer)?,
+            Command::Build => {
+                ensure_solution_built(self.config, self.year, self.day)
+                    .context("Failed to build solution")?;
+                self.reload_if_rebuilt()?;
+            }
+            Command::Help => println!("{HELP}"),
+            Command::Quit => unreachable!("handled by the caller"),
+        }
+        Ok(())
+    }
+
+    fn reload_if_rebuilt(&mut self) -> Result<()> {
+        if modified(&self.library_path)? == self.loaded.built {
+            return Ok(());
+        }
+        self.generation += 1;
+        self.loaded = Loaded::load(&self.library_path, self.generation)?;
+        info!("reloaded {}", self.library_path.display());
+        Ok(())
+    }
+
+    fn solve(&mut self) -> Result<()> {
+        self.reload_if_rebuilt()?;
+        let input = self.last_input.as_deref().context("nothing pasted yet")?;
+
+        let implementation = self.implementation.as_deref().unwrap_or_default();
+        let report = solve(&self.loaded.library, input, self.part, implementation)
+            .context("Failed to solve, try :build if the library is out of date")?;
+        let elapsed = format!("{:.2?}", Duration::from_secs_f64(report.elapsed_secs))
+            .bright_black()
+            .to_string();
+        let part = self.part as u8;
+        match (&report.answer, report.outcome) {
+            (Some(answer), _) => self.say(format!(
+                "part {part}: {}  {elapsed}",
+                answer.bright_white().bold()
+            )),
+            (None, outcome) => self.say(format!(
+                "part {part}: {} {}  {elapsed}",
+                if outcome == Outcome::Panic {
+                    "PANIC"
+                } else {
+                    "ERROR"
+                }
+                .red()
+                .bold(),
+                report.message.as_deref().unwrap_or_default().bright_red()
+            )),
+        }
+        self.last_report = Some(report);
+        Ok(())
+    }
+
+    fn save(&self, answer: Option<String>) -> Result<()> {
+        let input = self.last_input.as_deref().context("nothing pasted yet")?;
+        let literal = match answer {
+            Some(answer) => cases::answer_literal(&answer),
+            None => self
+                .last_report
+                .as_ref()
+                .and_then(|report| report.answer_literal.clone())
+                .context("no answer to save, give one with :save ANSWER")?,
+        };
+
+        let name = cases::insert_example(
+            &self.source,
+            EXAMPLE_PREFIX,
+            self.part as u8,
+            &literal,
+            input,
+        )?;
+        warn!(
+            "saved as example_part{}({literal}, {name}) in {}",
+            self.part as u8,
+            self.source.display()
+        );
+        Ok(())
+    }
+}
+
+/// Solves one input with a loaded solution library, an empty `implementation` being the default
+pub fn solve(
+    library: &Library,
+    input: &str,
+    part: PartNumber,
+    implementation: &str,
+) -> Result<SnippetReport> {
+    let mut report: Option<SnippetReport> = None;
+    let options = SnippetOptions {
+        input_ptr: input.as_ptr(),
+        input_len_bytes: input.len(),
+        part: part as u8,
+        impl_ptr: implementation.as_ptr(),
+        impl_len_bytes: implementation.len(),
+        report_sink: Some(sink),
+        report_ctx: &mut report as *mut Option<SnippetReport> as *mut c_void,
+    };
+    unsafe {
+        let func: Symbol<SnippetEntrypointFn> = library
+            .get(b"snippet_entrypoint")
+            .context("Failed to load snippet_entrypoint symbol")?;
+        func(&options);
+    }
+    report.context("solution sent no result")
+}
+
+/// Whether [`solve`] can drive the library, which may be built from another revision
+pub fn check_abi(library: &Library) -> Result<()> {
+    let version = unsafe {
+        library.get::<SnippetEntrypointFn>(b"snippet_entrypoint")?;
+        library.get::<AbiVersionFn>(b"snippet_abi_version")?()
+    };
+    anyhow::ensure!(
+        version == ABI_VERSION,
+        "it solves snippets with ABI version {version}, not {ABI_VERSION}"
+    );
+    Ok(())
+}
+
+fn modified(path: &Path) -> Result<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .with_context(|| format!("Failed to read {}", path.display()))
+}
+
+/// # Safety
+/// `ctx` must be the `Option<SnippetReport>` passed in [`SnippetOptions`].
+unsafe extern "C" fn sink(ctx: *mut c_void, report_ptr: *const u8, report_len: usize) {
+    let report = &mut *(ctx as *mut Option<SnippetReport>);
+    let bytes = std::slice::from_raw_parts(report_ptr, report_len);
+    *report = SnippetReport::decode(bytes).ok();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reader() {
+        let mut reader = Reader::default();
+        assert_eq!(
+            reader.line(":part 2"),
+            Some(Entry::Command(Ok(Command::Part(PartNumber::Part2))))
+        );
+        assert_eq!(reader.line("p=0,4 v=3,-3"), None);
+        // a pasted line can start with a colon
+        assert_eq!(reader.line(":x"), None);
+        assert_eq!(
+            reader.line("."),
+            Some(Entry::Input("p=0,4 v=3,-3\n:x".to_owned()))
+        );
+
+        assert_eq!(
+            reader.line(":save  3,5,0 "),
+            Some(Entry::Command(Ok(Command::Save(Some("3,5,0".to_owned())))))
+        );
+        assert_eq!(
+            reader.line(":impl"),
+            Some(Entry::Command(Ok(Command::Impl(None))))
+        );
+        assert!(matches!(
+            reader.line(":part 3"),
+            Some(Entry::Command(Err(_)))
+        ));
+
+        assert_eq!(reader.line("1"), None);
+        assert_eq!(reader.finish(), Some(Entry::Input("1".to_owned())));
+        assert_eq!(reader.finish(), None);
+    }
+}
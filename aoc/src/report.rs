@@ -0,0 +1,684 @@
+use crate::config::OutputFormat;
+use anyhow::{Context, Result};
+use common::itertools::Itertools;
+use common::report::{CaseReport, Outcome, ReportSinkFn};
+use common::strip_ansi;
+use owo_colors::OwoColorize;
+use serde::{Deserialize, Serialize};
+use std::ffi::c_void;
+use std::fmt::Write as _;
+use std::io::{Read, Write};
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::JoinHandle;
+
+/// Written to the captured stdout at each report, so output can be split between cases
+const BOUNDARY: &[u8] = b"\0aoc-case-boundary\0";
+
+/// A day's cases with what was printed while each ran
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DayReport {
+    pub year: u32,
+    pub day: u32,
+    pub cases: Vec<CapturedCase>,
+    /// Why the day couldn't run, e.g. a missing input or failed build
+    pub error: Option<String>,
+    /// The solution's source file, if it's known
+    pub source: Option<PathBuf>,
+    /// High-water mark of the runner, or of solution programs for external solutions, after the
+    /// day ran. It never goes down, so it only says something about days that raise it.
+    pub peak_rss_kb: Option<u64>,
+    /// Fingerprint of the input it ran against, if it could be read
+    pub input_hash: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CapturedCase {
+    pub report: CaseReport,
+    pub output: String,
+}
+
+/// Collects [`CaseReport`]s from each day run. For JUnit and TAP output, stdout is captured
+/// while days run so it doesn't end up mixed in with the report. For the HTML report it's
+/// captured too, but still shown.
+pub struct Reporter {
+    format: OutputFormat,
+    capture: bool,
+    days: Vec<DayReport>,
+    current: Option<(DayReport, Option<Capture>)>,
+    /// In-process solutions end their worker when they time out, so results are written then
+    in_process: bool,
+    /// Set in workers, which hand their days to the parent as JSON instead of rendering them
+    worker_output: Option<PathBuf>,
+}
+
+impl Reporter {
+    /// `capture` keeps what each case printed, which JUnit and TAP always do
+    pub fn new(format: OutputFormat, capture: bool) -> Self {
+        Self {
+            format,
+            capture: capture || format != OutputFormat::Human,
+            days: Vec::new(),
+            current: None,
+            in_process: false,
+            worker_output: None,
+        }
+    }
+
+    /// For a worker of a parallel run, which always captures so the parent has each case's output
+    pub fn worker(format: OutputFormat, output: PathBuf) -> Self {
+        Self {
+            worker_output: Some(output),
+            ..Self::new(format, true)
+        }
+    }
+
+    /// The days a worker wrote with [`Reporter::finish`]
+    pub fn read_worker_output(path: &Path) -> Result<Vec<DayReport>> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&text).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    /// Adds a day run by a worker
+    pub fn push_day(&mut self, day: DayReport) {
+        self.days.push(day);
+    }
+
+    pub fn is_structured(&self) -> bool {
+        self.format != OutputFormat::Human
+    }
+
+    pub fn begin_day(
+        &mut self,
+        year: u32,
+        day: u32,
+        in_process: bool,
+        source: Option<PathBuf>,
+        input_hash: Option<String>,
+    ) -> Result<()> {
+        let report = DayReport {
+            year,
+            day,
+            cases: Vec::new(),
+            error: None,
+            source,
+            peak_rss_kb: None,
+            input_hash,
+        };
+        let echo = !self.is_structured();
+        let capture = self.capture.then(|| Capture::start(echo)).transpose()?;
+        self.current = Some((report, capture));
+        self.in_process = in_process;
+        Ok(())
+    }
+
+    pub fn end_day(&mut self, error: Option<String>) {
+        let Some((mut report, capture)) = self.current.take() else {
+            return;
+        };
+
+        // printed after the last case, like the summary
+        let trailing = capture.map(Capture::stop).unwrap_or_default();
+        if let Some(last) = report.cases.last_mut() {
+            last.output.push_str(&trailing);
+        }
+        report.error = error;
+        report.peak_rss_kb = peak_rss_kb(if self.in_process {
+            libc::RUSAGE_SELF
+        } else {
+            libc::RUSAGE_CHILDREN
+        });
+        self.days.push(report);
+    }
+
+    /// For [`common::RunConfig::report_sink`]
+    pub fn sink(&mut self) -> (ReportSinkFn, *mut c_void) {
+        (Self::sink_fn, self as *mut Self as *mut c_void)
+    }
+
+    pub fn days(&self) -> &[DayReport] {
+        &self.days
+    }
+
+    /// # Safety
+    /// `ctx` must come from [`Reporter::sink`] and `report` be valid for the call.
+    unsafe extern "C" fn sink_fn(ctx: *mut c_void, report_ptr: *const u8, report_len: usize) {
+        let reporter = &mut *(ctx as *mut Self);
+        let bytes = std::slice::from_raw_parts(report_ptr, report_len);
+        let Ok(report) = CaseReport::decode(bytes) else {
+            return;
+        };
+        let Some((day, capture)) = &mut reporter.current else {
+            return;
+        };
+
+        let timed_out = report.outcome == Outcome::Timeout;
+        day.cases.push(CapturedCase {
+            report,
+            output: capture.as_mut().map(Capture::take).unwrap_or_default(),
+        });
+
+        if timed_out && reporter.in_process && reporter.worker_output.is_some() {
+            reporter.end_day(None);
+            if let Err(e) = reporter.finish() {
+                log::error!("{e:#}");
+            }
+        }
+    }
+
+    /// Prints the rendered report, or for a worker, writes its days out for the parent
+    pub fn finish(&self) -> Result<()> {
+        match &self.worker_output {
+            Some(path) => {
+                let json = serde_json::to_string(&self.days).context("Failed to serialise days")?;
+                std::fs::write(path, json)
+                    .with_context(|| format!("Failed to write {}", path.display()))
+            }
+            None => {
+                print!("{}", self.render());
+                std::io::stdout().flush().context("Failed to flush stdout")
+            }
+        }
+    }
+
+    pub fn render(&self) -> String {
+        match self.format {
+            OutputFormat::Human => String::new(),
+            OutputFormat::Junit => junit(&self.days),
+            OutputFormat::Tap => tap(&self.days),
+        }
+    }
+}
+
+/// Linux reports `ru_maxrss` in kilobytes
+fn peak_rss_kb(who: libc::c_int) -> Option<u64> {
+    let mut usage = unsafe { std::mem::zeroed::<libc::rusage>() };
+    (unsafe { libc::getrusage(who, &mut usage) } == 0 && usage.ru_maxrss > 0)
+        .then_some(usage.ru_maxrss as u64)
+}
+
+/// Redirects stdout into a pipe drained by a thread, until stopped
+pub struct Capture {
+    saved_stdout: OwnedFd,
+    /// Pass output on to the real stdout as each case finishes
+    echo: bool,
+    captured: Arc<(Mutex<Vec<u8>>, Condvar)>,
+    reader: JoinHandle<()>,
+}
+
+impl Capture {
+    pub fn start(echo: bool) -> Result<Self> {
+        std::io::stdout().flush().ok();
+
+        let mut fds = [0; 2];
+        if unsafe { libc::pipe(fds.as_mut_ptr()) } != 0 {
+            return Err(std::io::Error::last_os_error()).context("Failed to create pipe");
+        }
+        let (read_end, write_end) =
+            unsafe { (OwnedFd::from_raw_fd(fds[0]), OwnedFd::from_raw_fd(fds[1])) };
+
+        let saved = unsafe { libc::dup(libc::STDOUT_FILENO) };
+        if saved < 0 || unsafe { libc::dup2(write_end.as_raw_fd(), libc::STDOUT_FILENO) } < 0 {
+            return Err(std::io::Error::last_os_error()).context("Failed to redirect stdout");
+        }
+        // stdout is now the only write end, so the reader sees EOF once it's restored
+        drop(write_end);
+
+        let captured = Arc::new((Mutex::new(Vec::new()), Condvar::new()));
+        let reader = std::thread::spawn({
+            let captured = captured.clone();
+            move || {
+                let mut pipe = std::fs::File::from(read_end);
+                let mut buf = [0; 4096];
+                while let Ok(n @ 1..) = pipe.read(&mut buf) {
+                    let (lock, changed) = &*captured;
+                    lock.lock().unwrap().extend_from_slice(&buf[..n]);
+                    changed.notify_all();
+                }
+            }
+        });
+
+        Ok(Self {
+            saved_stdout: unsafe { OwnedFd::from_raw_fd(saved) },
+            echo,
+            captured,
+            reader,
+        })
+    }
+
+    /// Everything printed since the last call
+    fn take(&mut self) -> String {
+        std::io::stdout().flush().ok();
+        unsafe {
+            libc::write(
+                libc::STDOUT_FILENO,
+                BOUNDARY.as_ptr() as *const c_void,
+                BOUNDARY.len(),
+            )
+        };
+
+        let (lock, changed) = &*self.captured;
+        let mut captured = changed
+            .wait_while(lock.lock().unwrap(), |buf| find(buf, BOUNDARY).is_none())
+            .unwrap();
+        let at = find(&captured, BOUNDARY).expect("waited for boundary");
+        let output = captured[..at].to_vec();
+        captured.drain(..at + BOUNDARY.len());
+        drop(captured);
+
+        if self.echo {
+            let mut stdout =
+                std::fs::File::from(self.saved_stdout.try_clone().expect("dup stdout"));
+            stdout.write_all(&output).ok();
+        }
+        String::from_utf8_lossy(&output).into_owned()
+    }
+
+    /// Restores stdout, returning anything printed since the last [`Capture::take`]
+    pub fn stop(self) -> String {
+        std::io::stdout().flush().ok();
+        unsafe { libc::dup2(self.saved_stdout.as_raw_fd(), libc::STDOUT_FILENO) };
+        self.reader.join().ok();
+
+        let captured = self.captured.0.lock().unwrap();
+        if self.echo {
+            std::io::stdout().write_all(&captured).ok();
+        }
+        String::from_utf8_lossy(&captured).into_owned()
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+/// Cases passed per year and overall, for runs of more than one day, naming the days that failed
+/// or couldn't run
+pub fn summary(days: &[DayReport], colour: bool) -> String {
+    let passed = |days: &[&DayReport]| {
+        let cases = days.iter().flat_map(|day| &day.cases);
+        let total = cases.clone().count();
+        let passed = cases
+            .filter(|case| failure_type(case.report.outcome).is_none())
+            .count();
+        let passed = if passed == total {
+            passed.green().to_string()
+        } else {
+            passed.red().to_string()
+        };
+        format!("{passed} of {total} cases passed")
+    };
+    let day_list = |days: &[&DayReport], pick: fn(&DayReport) -> bool| {
+        days.iter()
+            .filter(|day| pick(day))
+            .map(|day| format!("{:02}", day.day))
+            .join(" ")
+    };
+
+    let mut out = format!(
+        "
+{}
+",
+        "Summary".bold()
+    );
+    for (year, days) in &days.iter().chunk_by(|day| day.year) {
+        let days = days.collect_vec();
+        write!(out, "  {year}  {}", passed(&days)).unwrap();
+        let failing = day_list(&days, |day| {
+            day.cases
+                .iter()
+                .any(|case| failure_type(case.report.outcome).is_some())
+        });
+        if !failing.is_empty() {
+            write!(out, ", failing: {}", failing.red()).unwrap();
+        }
+        let not_run = day_list(&days, |day| day.error.is_some());
+        if !not_run.is_empty() {
+            write!(out, ", couldn't run: {}", not_run.yellow()).unwrap();
+        }
+        out.push('\n');
+    }
+    writeln!(
+        out,
+        "  {}   {} across {} days",
+        "All".bold(),
+        passed(&days.iter().collect_vec()),
+        days.len()
+    )
+    .unwrap();
+
+    if colour {
+        out
+    } else {
+        strip_ansi(&out)
+    }
+}
+
+/// Failure type in the report, `None` if the case passed
+pub fn failure_type(outcome: Outcome) -> Option<&'static str> {
+    match outcome {
+        Outcome::Pass | Outcome::Unchecked => None,
+        Outcome::WrongAnswer => Some("wrong-answer"),
+        Outcome::Disagreement => Some("disagreement"),
+        Outcome::Error => Some("error"),
+        Outcome::Panic => Some("panic"),
+        Outcome::Timeout => Some("timeout"),
+    }
+}
+
+/// A crash rather than a wrong answer, reported as a JUnit `<error>`
+fn is_error(outcome: Outcome) -> bool {
+    matches!(outcome, Outcome::Error | Outcome::Panic | Outcome::Timeout)
+}
+
+impl CapturedCase {
+    fn name(&self) -> String {
+        let report = &self.report;
+        let input = if report.full_input {
+            "input"
+        } else {
+            "example"
+        };
+        let mut name = format!("part {} case #{} {input}", report.part, report.case);
+        if let Some(implementation) = &report.implementation {
+            write!(name, " [{implementation}]").unwrap();
+        }
+        name
+    }
+
+    fn failure_message(&self) -> String {
+        let report = &self.report;
+        match (report.outcome, &report.expected, &report.answer) {
+            (Outcome::WrongAnswer, Some(expected), Some(answer)) => match &report.message {
+                Some(message) => format!("expected {expected}, got {answer} ({message})"),
+                None => format!("expected {expected}, got {answer}"),
+            },
+            _ => report.message.clone().unwrap_or_default(),
+        }
+    }
+}
+
+fn junit(days: &[DayReport]) -> String {
+    let cases = days.iter().flat_map(|day| &day.cases);
+    let count = |f: fn(Outcome) -> bool| cases.clone().filter(|c| f(c.report.outcome)).count();
+    let errors = |day: &DayReport| {
+        day.cases
+            .iter()
+            .filter(|c| is_error(c.report.outcome))
+            .count()
+            + usize::from(day.error.is_some())
+    };
+    let failures = |day: &DayReport| {
+        day.cases
+            .iter()
+            .filter(|c| failure_type(c.report.outcome).is_some() && !is_error(c.report.outcome))
+            .count()
+    };
+    // summing no floats gives -0.0
+    let time = |day: &DayReport| {
+        day.cases
+            .iter()
+            .filter_map(|c| c.report.elapsed_secs)
+            .fold(0.0, |total, secs| total + secs)
+    };
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    writeln!(
+        xml,
+        "<testsuites name=\"aoc\" tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{:.6}\">",
+        cases.clone().count() + days.iter().filter(|d| d.error.is_some()).count(),
+        count(|o| failure_type(o).is_some() && !is_error(o)),
+        days.iter().map(errors).sum::<usize>(),
+        days.iter().map(time).sum::<f64>(),
+    )
+    .unwrap();
+
+    for day in days {
+        let suite = format!("{}-{:02}", day.year, day.day);
+        writeln!(
+            xml,
+            "  <testsuite name=\"{suite}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{:.6}\">",
+            day.cases.len() + usize::from(day.error.is_some()),
+            failures(day),
+            errors(day),
+            time(day),
+        )
+        .unwrap();
+
+        let classname = format!("aoc.{}.{:02}", day.year, day.day);
+        if let Some(error) = &day.error {
+            writeln!(
+                xml,
+                "    <testcase classname=\"{classname}\" name=\"setup\">\n      <error type=\"setup\" message=\"{}\"/>\n    </testcase>",
+                escape(error)
+            )
+            .unwrap();
+        }
+
+        for case in &day.cases {
+            let report = &case.report;
+            write!(
+                xml,
+                "    <testcase classname=\"{classname}\" name=\"{}\"",
+                escape(&case.name())
+            )
+            .unwrap();
+            if let Some(elapsed) = report.elapsed_secs {
+                write!(xml, " time=\"{elapsed:.6}\"").unwrap();
+            }
+            xml.push_str(">\n");
+
+            if let Some(answer) = &report.answer {
+                writeln!(
+                    xml,
+                    "      <properties><property name=\"answer\" value=\"{}\"/></properties>",
+                    escape(answer)
+                )
+                .unwrap();
+            }
+            if let Some(failure_type) = failure_type(report.outcome) {
+                let element = if is_error(report.outcome) {
+                    "error"
+                } else {
+                    "failure"
+                };
+                writeln!(
+                    xml,
+                    "      <{element} type=\"{failure_type}\" message=\"{}\"/>",
+                    escape(&case.failure_message())
+                )
+                .unwrap();
+            }
+            if !case.output.trim().is_empty() {
+                writeln!(
+                    xml,
+                    "      <system-out><![CDATA[{}]]></system-out>",
+                    xml_chars(&case.output).replace("]]>", "]]]]><![CDATA[>")
+                )
+                .unwrap();
+            }
+            xml.push_str("    </testcase>\n");
+        }
+
+        xml.push_str("  </testsuite>\n");
+    }
+
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+fn tap(days: &[DayReport]) -> String {
+    let total: usize = days
+        .iter()
+        .map(|day| day.cases.len() + usize::from(day.error.is_some()))
+        .sum();
+
+    let mut out = format!("TAP version 13\n1..{total}\n");
+    let mut n = 0;
+    for day in days {
+        let suite = format!("{}-{:02}", day.year, day.day);
+        if let Some(error) = &day.error {
+            n += 1;
+            writeln!(out, "not ok {n} - {suite} setup").unwrap();
+            yaml_block(
+                &mut out,
+                &[
+                    ("type", Some(quote("setup"))),
+                    ("message", Some(quote(error))),
+                ],
+                None,
+            );
+        }
+
+        for case in &day.cases {
+            n += 1;
+            let report = &case.report;
+            let status = if failure_type(report.outcome).is_some() {
+                "not ok"
+            } else {
+                "ok"
+            };
+            writeln!(out, "{status} {n} - {suite} {}", case.name()).unwrap();
+
+            yaml_block(
+                &mut out,
+                &[
+                    ("type", failure_type(report.outcome).map(quote)),
+                    (
+                        "message",
+                        failure_type(report.outcome).map(|_| quote(&case.failure_message())),
+                    ),
+                    ("answer", report.answer.as_deref().map(quote)),
+                    ("expected", report.expected.as_deref().map(quote)),
+                    (
+                        "duration_ms",
+                        report
+                            .elapsed_secs
+                            .map(|secs| format!("{:.3}", secs * 1000.0)),
+                    ),
+                ],
+                Some(&case.output),
+            );
+        }
+    }
+
+    out
+}
+
+/// TAP 13 diagnostics from YAML values, skipping unset fields
+fn yaml_block(out: &mut String, fields: &[(&str, Option<String>)], output: Option<&str>) {
+    out.push_str("  ---\n");
+    for (key, value) in fields {
+        if let Some(value) = value {
+            writeln!(out, "  {key}: {value}").unwrap();
+        }
+    }
+    if let Some(output) = output.filter(|o| !o.trim().is_empty()) {
+        out.push_str("  output: |\n");
+        for line in output.trim_matches('\n').lines() {
+            writeln!(out, "    {line}").unwrap();
+        }
+    }
+    out.push_str("  ...\n");
+}
+
+/// JSON strings are valid YAML
+fn quote(s: &str) -> String {
+    serde_json::to_string(s).unwrap()
+}
+
+pub fn escape(s: &str) -> String {
+    xml_chars(s)
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\n', "&#10;")
+}
+
+/// Drops characters XML can't contain at all, like the escapes in coloured output
+fn xml_chars(s: &str) -> String {
+    s.chars()
+        .filter(|&c| matches!(c, '\t' | '\n' | '\r') || c >= ' ')
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn case(outcome: Outcome, answer: &str, expected: Option<&str>, output: &str) -> CapturedCase {
+        CapturedCase {
+            report: CaseReport {
+                case: 1,
+                part: 1,
+                full_input: false,
+                implementation: None,
+                outcome,
+                answer: Some(answer.to_owned()),
+                expected: expected.map(str::to_owned),
+                message: None,
+                elapsed_secs: Some(0.5),
+            },
+            output: output.to_owned(),
+        }
+    }
+
+    fn days() -> Vec<DayReport> {
+        vec![DayReport {
+            year: 2024,
+            day: 3,
+            cases: vec![
+                case(Outcome::Pass, "161", Some("161"), ""),
+                case(Outcome::WrongAnswer, "12", Some("48"), "debug <line>\n"),
+            ],
+            error: None,
+            source: None,
+            peak_rss_kb: None,
+            input_hash: None,
+        }]
+    }
+
+    #[test]
+    fn test_junit() {
+        let xml = junit(&days());
+        assert!(xml.contains(
+            "<testsuite name=\"2024-03\" tests=\"2\" failures=\"1\" errors=\"0\" time=\"1.000000\">"
+        ));
+        assert!(xml.contains("<failure type=\"wrong-answer\" message=\"expected 48, got 12\"/>"));
+        assert!(xml.contains("<system-out><![CDATA[debug <line>\n]]></system-out>"));
+    }
+
+    #[test]
+    fn test_tap() {
+        let tap = tap(&days());
+        assert!(tap.starts_with("TAP version 13\n1..2\n"));
+        assert!(tap.contains("ok 1 - 2024-03 part 1 case #1 example\n"));
+        assert!(tap.contains("not ok 2 - 2024-03 part 1 case #1 example\n"));
+        assert!(tap.contains("  type: \"wrong-answer\"\n"));
+        assert!(tap.contains("  output: |\n    debug <line>\n"));
+    }
+
+    #[test]
+    fn test_summary() {
+        let mut run = days();
+        run.push(DayReport {
+            day: 4,
+            cases: Vec::new(),
+            error: Some("no input".to_owned()),
+            ..days().remove(0)
+        });
+        run.push(DayReport {
+            year: 2025,
+            cases: vec![case(Outcome::Pass, "1", Some("1"), "")],
+            ..days().remove(0)
+        });
+        assert_eq!(
+            summary(&run, false),
+            "\nSummary\n  2024  1 of 2 cases passed, failing: 03, couldn't run: 04\n  \
+             2025  1 of 1 cases passed\n  All   2 of 3 cases passed across 3 days\n"
+        );
+    }
+}
@@ -0,0 +1,139 @@
+use crate::answers::{Answers, PartRecord};
+use crate::calendar;
+use anyhow::{Context, Result};
+use owo_colors::OwoColorize;
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::path::Path;
+use std::time::Duration;
+
+/// `aoc stars --markdown` replaces everything between these
+pub const START_MARKER: &str = "<!-- aoc-stars:start -->";
+pub const END_MARKER: &str = "<!-- aoc-stars:end -->";
+
+const DAYS_PER_ROW: u32 = 5;
+
+/// A year as a grid of days: `**` for both stars, `*` for one, `.` for a solution without any
+pub fn calendar(
+    year: u32,
+    answers: &Answers,
+    solutions: &HashSet<(u32, u32)>,
+    colour: bool,
+) -> String {
+    let stars = |day| answers.get(year, day).map_or(0, |record| record.stars());
+    let total: usize = calendar::days(year).map(stars).sum();
+
+    let mut out = format!("{year}  {total} stars\n");
+    for day in calendar::days(year) {
+        let (cell, paint): (_, fn(&str) -> String) = match stars(day) {
+            2 => ("**", |s| s.bright_yellow().bold().to_string()),
+            1 => ("* ", |s| s.bright_white().to_string()),
+            _ if solutions.contains(&(year, day)) => (". ", |s| s.bright_black().to_string()),
+            _ => ("  ", str::to_owned),
+        };
+        let cell = if colour { paint(cell) } else { cell.to_owned() };
+        write!(out, "  {day:>2} {cell}").unwrap();
+        if day % DAYS_PER_ROW == 0 {
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// Per-year tables of each day with a solution or recorded answer, with runtimes
+pub fn markdown(years: &[u32], answers: &Answers, solutions: &HashSet<(u32, u32)>) -> String {
+    let part = |part: &Option<PartRecord>| match part {
+        Some(PartRecord {
+            passed: true,
+            elapsed_ms,
+            ..
+        }) => match elapsed_ms {
+            Some(ms) => format!("⭐ {:.2?}", Duration::from_secs_f64(ms / 1000.0)),
+            None => "⭐".to_owned(),
+        },
+        Some(_) => "✗".to_owned(),
+        None => String::new(),
+    };
+
+    let mut out = String::new();
+    for &year in years {
+        let days = calendar::days(year)
+            .filter(|&day| solutions.contains(&(year, day)) || answers.get(year, day).is_some())
+            .collect::<Vec<_>>();
+        if days.is_empty() {
+            continue;
+        }
+
+        let total: usize = days
+            .iter()
+            .filter_map(|&day| answers.get(year, day))
+            .map(|record| record.stars())
+            .sum();
+        writeln!(out, "### {year} ({total} ⭐)\n").unwrap();
+        out.push_str("| Day | Part 1 | Part 2 |\n|----:|:------:|:------:|\n");
+        for day in days {
+            let record = answers.get(year, day).cloned().unwrap_or_default();
+            writeln!(
+                out,
+                "| {day} | {} | {} |",
+                part(&record.part1),
+                part(&record.part2)
+            )
+            .unwrap();
+        }
+        out.push('\n');
+    }
+    out.truncate(out.trim_end().len());
+    out
+}
+
+/// Replaces what's between the markers in `path` with `content`
+pub fn update_markdown(path: &Path, content: &str) -> Result<()> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let text = replace_between_markers(&text, content).with_context(|| {
+        format!(
+            "{} needs `{START_MARKER}` and `{END_MARKER}` lines to put the table between",
+            path.display()
+        )
+    })?;
+    std::fs::write(path, text).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+fn replace_between_markers(text: &str, content: &str) -> Option<String> {
+    let start = text.find(START_MARKER)? + START_MARKER.len();
+    let end = start + text[start..].find(END_MARKER)?;
+    Some(format!("{}\n{content}\n{}", &text[..start], &text[end..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markdown() {
+        let answers: Answers = toml::from_str(
+            "[2024-01.part1]\nanswer = \"11\"\npassed = true\nelapsed_ms = 1.5\n\
+             [2024-01.part2]\nanswer = \"31\"\npassed = false\n",
+        )
+        .unwrap();
+        let solutions = HashSet::from([(2024, 1), (2024, 2)]);
+
+        let table = markdown(&[2024], &answers, &solutions);
+        assert_eq!(
+            table,
+            "### 2024 (1 ⭐)\n\n| Day | Part 1 | Part 2 |\n|----:|:------:|:------:|\n\
+             | 1 | ⭐ 1.50ms | ✗ |\n| 2 |  |  |"
+        );
+
+        let grid = calendar(2024, &answers, &solutions, false);
+        assert!(grid.starts_with("2024  1 stars\n   1 *    2 .    3   "));
+
+        let readme = format!("# AoC\n{START_MARKER}\nold\n{END_MARKER}\nmore");
+        assert_eq!(
+            replace_between_markers(&readme, "new").unwrap(),
+            format!("# AoC\n{START_MARKER}\nnew\n{END_MARKER}\nmore")
+        );
+        assert!(replace_between_markers("# AoC", "new").is_none());
+    }
+}
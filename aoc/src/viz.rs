@@ -0,0 +1,273 @@
+use anyhow::{Context, Result};
+use common::viz::{Frame, Rgb};
+use log::{info, warn};
+use owo_colors::OwoColorize;
+use std::ffi::c_void;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Pixel size of a character cell and of a pixel frame's pixel in exported images
+const CELL_SIZE: usize = 8;
+const PIXEL_SIZE: usize = 4;
+
+const BACKGROUND: Rgb = Rgb(16, 16, 16);
+const DEFAULT_FG: Rgb = Rgb(200, 200, 200);
+
+#[derive(Debug, Clone)]
+pub enum VizOutput {
+    Terminal,
+    Gif(PathBuf),
+    /// Numbered files next to the given path
+    PngSequence(PathBuf),
+}
+
+impl VizOutput {
+    pub fn parse(s: &str) -> Result<Self> {
+        let path = PathBuf::from(s);
+        match path.extension().and_then(|e| e.to_str()) {
+            _ if s == "term" => Ok(Self::Terminal),
+            Some("gif") => Ok(Self::Gif(path)),
+            Some("png") => Ok(Self::PngSequence(path)),
+            _ => anyhow::bail!("--viz expects `term`, a .gif path or a .png path"),
+        }
+    }
+}
+
+/// Collects frames sent over FFI, which the solution has already thinned out to `--viz-every`
+#[derive(Default)]
+pub struct Recording {
+    pub frames: Vec<Frame>,
+}
+
+impl Recording {
+    pub fn as_ctx(&mut self) -> *mut c_void {
+        self as *mut Self as *mut c_void
+    }
+
+    /// # Safety
+    /// `ctx` must come from [`Recording::as_ctx`] and `frame_ptr` point to `frame_len` bytes.
+    pub unsafe extern "C" fn sink(ctx: *mut c_void, frame_ptr: *const u8, frame_len: usize) {
+        let recording = &mut *(ctx as *mut Self);
+        match Frame::decode(std::slice::from_raw_parts(frame_ptr, frame_len)) {
+            Ok(frame) => recording.frames.push(frame),
+            Err(e) => warn!("dropping undecodable frame: {e}"),
+        }
+    }
+
+    pub fn output(&self, output: &VizOutput, fps: u32) -> Result<()> {
+        if self.frames.is_empty() {
+            warn!("solution recorded no frames");
+            return Ok(());
+        }
+
+        info!("recorded {} frames", self.frames.len());
+
+        match output {
+            VizOutput::Terminal => play_in_terminal(&self.frames, fps),
+            VizOutput::Gif(path) => export_gif(&self.frames, path, fps),
+            VizOutput::PngSequence(path) => export_pngs(&self.frames, path),
+        }
+    }
+}
+
+fn play_in_terminal(frames: &[Frame], fps: u32) -> Result<()> {
+    let delay = Duration::from_secs_f64(1.0 / fps.max(1) as f64);
+    let mut stdout = std::io::stdout().lock();
+
+    for (i, frame) in frames.iter().enumerate() {
+        let mut buf = String::from("\x1b[H\x1b[2J");
+        match frame {
+            Frame::Chars(f) => {
+                for y in 0..f.height {
+                    for x in 0..f.width {
+                        let cell = f.get(x, y);
+                        let Rgb(r, g, b) = cell.fg.unwrap_or(DEFAULT_FG);
+                        let s = cell.c.truecolor(r, g, b).to_string();
+                        buf += &match cell.bg {
+                            Some(Rgb(r, g, b)) => s.on_truecolor(r, g, b).to_string(),
+                            None => s,
+                        };
+                    }
+                    buf.push('\n');
+                }
+            }
+            Frame::Pixels(f) => {
+                // two pixels per character cell
+                for y in (0..f.height).step_by(2) {
+                    for x in 0..f.width {
+                        let Rgb(r, g, b) = f.get(x, y);
+                        let Rgb(br, bg, bb) = if y + 1 < f.height {
+                            f.get(x, y + 1)
+                        } else {
+                            BACKGROUND
+                        };
+                        buf += &"▀".truecolor(r, g, b).on_truecolor(br, bg, bb).to_string();
+                    }
+                    buf.push('\n');
+                }
+            }
+        }
+
+        buf += &format!("frame {}/{}\n", i + 1, frames.len());
+        stdout.write_all(buf.as_bytes())?;
+        stdout.flush()?;
+        std::thread::sleep(delay);
+    }
+
+    Ok(())
+}
+
+/// RGB bytes of a frame drawn onto a `width`x`height` canvas
+fn rasterise(frame: &Frame, width: usize, height: usize) -> Vec<u8> {
+    let mut canvas = vec![BACKGROUND; width * height];
+    let mut fill = |x0: usize, y0: usize, size: usize, colour: Rgb| {
+        for y in y0..(y0 + size).min(height) {
+            for x in x0..(x0 + size).min(width) {
+                canvas[y * width + x] = colour;
+            }
+        }
+    };
+
+    match frame {
+        Frame::Chars(f) => {
+            for y in 0..f.height {
+                for x in 0..f.width {
+                    let cell = f.get(x, y);
+                    if let Some(bg) = cell.bg {
+                        fill(x * CELL_SIZE, y * CELL_SIZE, CELL_SIZE, bg);
+                    }
+                    // characters become an inset square, empty-looking ones are left out
+                    if !matches!(cell.c, ' ' | '.') {
+                        fill(
+                            x * CELL_SIZE + 1,
+                            y * CELL_SIZE + 1,
+                            CELL_SIZE - 2,
+                            cell.fg.unwrap_or(DEFAULT_FG),
+                        );
+                    }
+                }
+            }
+        }
+        Frame::Pixels(f) => {
+            for y in 0..f.height {
+                for x in 0..f.width {
+                    fill(x * PIXEL_SIZE, y * PIXEL_SIZE, PIXEL_SIZE, f.get(x, y));
+                }
+            }
+        }
+    }
+
+    canvas
+        .into_iter()
+        .flat_map(|Rgb(r, g, b)| [r, g, b])
+        .collect()
+}
+
+fn image_size(frame: &Frame) -> (usize, usize) {
+    let scale = match frame {
+        Frame::Chars(_) => CELL_SIZE,
+        Frame::Pixels(_) => PIXEL_SIZE,
+    };
+    (frame.width() * scale, frame.height() * scale)
+}
+
+fn export_gif(frames: &[Frame], path: &Path, fps: u32) -> Result<()> {
+    // frames can change size, so use a canvas that fits them all
+    let (width, height) = frames
+        .iter()
+        .map(image_size)
+        .fold((1, 1), |(w, h), (fw, fh)| (w.max(fw), h.max(fh)));
+
+    let (Ok(gif_width), Ok(gif_height)) = (u16::try_from(width), u16::try_from(height)) else {
+        anyhow::bail!("{width}x{height} frames are too big for a gif, try --viz with a .png path");
+    };
+
+    let file =
+        File::create(path).with_context(|| format!("Failed to create {}", path.display()))?;
+    let mut encoder = gif::Encoder::new(BufWriter::new(file), gif_width, gif_height, &[])
+        .context("Failed to start gif")?;
+    encoder.set_repeat(gif::Repeat::Infinite)?;
+
+    let delay = (100 / fps.max(1)).max(1) as u16;
+    for frame in frames {
+        let rgb = rasterise(frame, width, height);
+        let mut gif_frame = gif::Frame::from_rgb_speed(gif_width, gif_height, &rgb, 10);
+        gif_frame.delay = delay;
+        encoder.write_frame(&gif_frame)?;
+    }
+
+    info!("wrote {} frames to {}", frames.len(), path.display());
+    Ok(())
+}
+
+fn export_pngs(frames: &[Frame], path: &Path) -> Result<()> {
+    let stem = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .context("png path has no file name")?;
+
+    for (i, frame) in frames.iter().enumerate() {
+        let (width, height) = image_size(frame);
+        let frame_path = path.with_file_name(format!("{stem}-{i:05}.png"));
+        let file = File::create(&frame_path)
+            .with_context(|| format!("Failed to create {}", frame_path.display()))?;
+
+        let mut encoder = png::Encoder::new(BufWriter::new(file), width as u32, height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .write_header()?
+            .write_image_data(&rasterise(frame, width, height))?;
+    }
+
+    info!(
+        "wrote {} frames to {}",
+        frames.len(),
+        path.with_file_name(format!("{stem}-*.png")).display()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::viz::{CharFrame, PixelFrame};
+
+    fn pixel(rgb: &[u8], width: usize, x: usize, y: usize) -> Rgb {
+        let i = (y * width + x) * 3;
+        Rgb(rgb[i], rgb[i + 1], rgb[i + 2])
+    }
+
+    #[test]
+    fn test_rasterise() {
+        let mut chars = CharFrame::from_text("#.");
+        chars.set_fg(0, 0, Rgb::RED);
+        chars.set_bg(1, 0, Rgb::BLUE);
+        let frame = Frame::from(chars);
+        let (width, height) = image_size(&frame);
+        assert_eq!((width, height), (2 * CELL_SIZE, CELL_SIZE));
+
+        let rgb = rasterise(&frame, width, height);
+        assert_eq!(rgb.len(), width * height * 3);
+        assert_eq!(pixel(&rgb, width, 0, 0), BACKGROUND);
+        assert_eq!(pixel(&rgb, width, 1, 1), Rgb::RED);
+        assert_eq!(pixel(&rgb, width, CELL_SIZE + 1, 1), Rgb::BLUE);
+
+        let mut pixels = PixelFrame::new(1, 1);
+        pixels.set(0, 0, Rgb::GREEN);
+        // on a canvas bigger than the frame, as gifs use for every frame
+        let rgb = rasterise(&Frame::from(pixels), 2 * PIXEL_SIZE, PIXEL_SIZE);
+        assert_eq!(pixel(&rgb, 2 * PIXEL_SIZE, PIXEL_SIZE - 1, 0), Rgb::GREEN);
+        assert_eq!(pixel(&rgb, 2 * PIXEL_SIZE, PIXEL_SIZE, 0), BACKGROUND);
+    }
+
+    #[test]
+    fn test_gif_too_big() {
+        let frame = Frame::from(CharFrame::new(u16::MAX as usize / CELL_SIZE + 1, 1));
+        let path = std::env::temp_dir().join(format!("aoc-too-big-{}.gif", std::process::id()));
+        assert!(export_gif(&[frame], &path, 10).is_err());
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,493 @@
+//! The wasm backend: solutions built for `wasm32-wasip1` and run under an embedded interpreter,
+//! with their instructions, memory and time per case limited
+
+use crate::config::Config;
+use crate::{cargo_build, log_outcome, runner_dir, solution_format, target_dir, Args};
+use anyhow::{Context, Result};
+use common::report::ReportSinkFn;
+use common::strip_ansi;
+use common::wasm::{self, WasmRunOptions};
+use log::info;
+use owo_colors::OwoColorize;
+use std::ffi::c_void;
+use std::hash::{BuildHasher, RandomState};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use wasmi::core::TrapCode;
+use wasmi::errors::{MemoryError, TableError};
+use wasmi::{Caller, Engine, Extern, ExternType, Linker, Module, ResourceLimiter, Store, Val};
+
+const WASI_MODULE: &str = "wasi_snapshot_preview1";
+const ERRNO_SUCCESS: i32 = 0;
+const ERRNO_BADF: i32 = 8;
+const ERRNO_IO: i32 = 29;
+const ERRNO_NOSYS: i32 = 52;
+const MIB: usize = 1 << 20;
+
+/// What one run of a solution's cases may use
+#[derive(Debug, Copy, Clone)]
+pub struct Limits {
+    /// Roughly one per wasm instruction
+    pub fuel: Option<u64>,
+    pub memory_bytes: usize,
+    /// Per case
+    pub timeout: Option<Duration>,
+    pub colour: bool,
+}
+
+impl Limits {
+    pub fn new(args: &Args) -> Self {
+        Self {
+            fuel: args.config.fuel,
+            memory_bytes: args.config.memory_mb as usize * MIB,
+            timeout: args.case_timeout(),
+            colour: args.config.colour_enabled(),
+        }
+    }
+}
+
+/// What host functions see of the runner
+struct Host {
+    report_sink: Option<(ReportSinkFn, *mut c_void)>,
+    memory: MemoryCap,
+    started: Instant,
+    /// Re-arms the watchdog as each case starts, with its report should it time out, and as
+    /// each finishes
+    case_done: Option<mpsc::Sender<Option<Vec<u8>>>>,
+}
+
+/// Refuses to grow memory past the limit, remembering that it did, as the solution then
+/// aborts like any other panic
+struct MemoryCap {
+    limit: usize,
+    exceeded: bool,
+}
+
+impl ResourceLimiter for MemoryCap {
+    fn memory_growing(
+        &mut self,
+        _current: usize,
+        desired: usize,
+        _maximum: Option<usize>,
+    ) -> Result<bool, MemoryError> {
+        self.exceeded |= desired > self.limit;
+        Ok(desired <= self.limit)
+    }
+
+    fn table_growing(
+        &mut self,
+        _current: u32,
+        _desired: u32,
+        _maximum: Option<u32>,
+    ) -> Result<bool, TableError> {
+        Ok(true)
+    }
+}
+
+/// Where cargo puts a solution built for the sandbox
+pub fn module_path(config: &Config, year: u32, day: u32) -> Result<PathBuf> {
+    Ok(target_dir()?
+        .join(wasm::TARGET)
+        .join(config.profile.dir_name())
+        .join(format!("{}.wasm", solution_format(year, day))))
+}
+
+/// [`crate::ensure_solutions_built`] for the sandbox's target
+pub fn ensure_built(config: &Config, days: &[(u32, u32)]) -> Result<()> {
+    cargo_build(&runner_dir()?, None, Some(wasm::TARGET), config, days).with_context(|| {
+        format!(
+            "Failed to build for {0}, is it installed? `rustup target add {0}`",
+            wasm::TARGET
+        )
+    })
+}
+
+/// [`crate::run_solution`] in the sandbox
+pub fn run_solution(
+    args: &Args,
+    input: &str,
+    report_sink: (ReportSinkFn, *mut c_void),
+) -> Result<()> {
+    anyhow::ensure!(args.viz.is_none(), "--viz needs the native backend");
+    let path = module_path(&args.config, args.year, args.day)?;
+    info!("loading sandboxed solution from {}", path.display());
+    let module =
+        std::fs::read(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+
+    let options = WasmRunOptions {
+        part_filter: args.part.map(|p| p as u8).unwrap_or(0),
+        case_filter: args.case.unwrap_or(0),
+        solutions_only: args.only_solutions,
+        implementation: args.implementation.clone().unwrap_or_default(),
+        bench: args.bench,
+        colour: args.config.colour_enabled(),
+        input_changed: args.input_changed,
+        timeout_ms: args.case_timeout().map_or(0, |t| t.as_millis() as u64),
+    };
+    info!("calling {}", wasm::RUN_CASES_EXPORT);
+    let passed = run_cases(
+        &module,
+        input,
+        &options,
+        Limits::new(args),
+        Some(report_sink),
+    )?;
+    log_outcome(passed);
+    Ok(())
+}
+
+/// Instantiates the module and runs its cases, returning whether they all passed
+fn run_cases(
+    module: &[u8],
+    input: &str,
+    options: &WasmRunOptions,
+    limits: Limits,
+    report_sink: Option<(ReportSinkFn, *mut c_void)>,
+) -> Result<bool> {
+    let mut engine_config = wasmi::Config::default();
+    engine_config.consume_fuel(limits.fuel.is_some());
+    let engine = Engine::new(&engine_config);
+    let module = Module::new(&engine, module).context("Failed to compile the wasm module")?;
+
+    let mut store = Store::new(
+        &engine,
+        Host {
+            report_sink,
+            memory: MemoryCap {
+                limit: limits.memory_bytes,
+                exceeded: false,
+            },
+            started: Instant::now(),
+            case_done: None,
+        },
+    );
+    store.limiter(|host| &mut host.memory);
+    if let Some(fuel) = limits.fuel {
+        store.set_fuel(fuel).map_err(wasmi::Error::from)?;
+    }
+
+    let instance = linker(&engine, &module)?
+        .instantiate(&mut store, &module)
+        .and_then(|pre| pre.start(&mut store))
+        .context("Failed to instantiate the wasm module")?;
+    // reactors run their static constructors here
+    if let Ok(initialize) = instance.get_typed_func::<(), ()>(&store, "_initialize") {
+        initialize.call(&mut store, ())?;
+    }
+    let memory = instance
+        .get_memory(&store, "memory")
+        .context("module doesn't export its memory")?;
+    let alloc = instance
+        .get_typed_func::<u32, u32>(&store, wasm::ALLOC_EXPORT)
+        .with_context(|| format!("module doesn't export {}", wasm::ALLOC_EXPORT))?;
+    let entrypoint = instance
+        .get_typed_func::<(u32, u32, u32, u32), i32>(&store, wasm::RUN_CASES_EXPORT)
+        .with_context(|| format!("module doesn't export {}", wasm::RUN_CASES_EXPORT))?;
+
+    let mut copy_in = |bytes: &[u8]| -> Result<(u32, u32)> {
+        let len = u32::try_from(bytes.len()).context("too big for wasm memory")?;
+        let ptr = alloc.call(&mut store, len)?;
+        memory
+            .write(&mut store, ptr as usize, bytes)
+            .map_err(wasmi::Error::from)?;
+        Ok((ptr, len))
+    };
+    let (input_ptr, input_len) = copy_in(input.as_bytes())?;
+    let (options_ptr, options_len) = copy_in(&serde_json::to_vec(options)?)?;
+
+    store.data_mut().case_done = limits
+        .timeout
+        .map(|timeout| watchdog(timeout, limits.colour, report_sink));
+    let result = entrypoint.call(&mut store, (input_ptr, input_len, options_ptr, options_len));
+    // disarms the watchdog
+    store.data_mut().case_done = None;
+
+    if let (Some(fuel), Ok(left)) = (limits.fuel, store.get_fuel()) {
+        info!("used {} of {fuel} fuel", fuel - left);
+    }
+    match result {
+        Ok(passed) => Ok(passed != 0),
+        Err(_) if store.data().memory.exceeded => anyhow::bail!(
+            "exceeded the memory limit of {} MiB",
+            limits.memory_bytes / MIB
+        ),
+        Err(e) => match (e.as_trap_code(), e.i32_exit_status()) {
+            (Some(TrapCode::OutOfFuel), _) => anyhow::bail!(
+                "ran out of fuel after {} instructions",
+                limits.fuel.unwrap_or_default()
+            ),
+            // panics abort in wasm, after the message is printed
+            (Some(TrapCode::UnreachableCodeReached), _) => anyhow::bail!("solution panicked"),
+            (_, Some(status)) => anyhow::bail!("solution exited with status {status}"),
+            _ => Err(e).context("solution trapped"),
+        },
+    }
+}
+
+/// Ends the process unless sent to within the timeout of starting or the last send. Neither
+/// backend can interrupt a running case, so this is what the native watchdog does too,
+/// including sending the running case's timeout report.
+fn watchdog(
+    timeout: Duration,
+    colour: bool,
+    report_sink: Option<(ReportSinkFn, *mut c_void)>,
+) -> mpsc::Sender<Option<Vec<u8>>> {
+    // stored as an address so the thread can take it
+    let report_sink = report_sink.map(|(sink, ctx)| (sink, ctx as usize));
+    let (case_done, cases_done) = mpsc::channel();
+    std::thread::spawn(move || {
+        let mut running: Option<Vec<u8>> = None;
+        loop {
+            match cases_done.recv_timeout(timeout) {
+                Ok(report) => running = report,
+                Err(mpsc::RecvTimeoutError::Disconnected) => return,
+                Err(mpsc::RecvTimeoutError::Timeout) => {
+                    let message = format!(
+                        "   ✗ {} sandboxed case: exceeded {timeout:?}",
+                        "TIMEOUT".red().bold()
+                    );
+                    println!(
+                        "{}",
+                        if colour {
+                            message
+                        } else {
+                            strip_ansi(&message)
+                        }
+                    );
+                    if let (Some(report), Some((sink, ctx))) = (&running, report_sink) {
+                        unsafe { sink(ctx as *mut c_void, report.as_ptr(), report.len()) };
+                    }
+                    std::process::exit(124);
+                }
+            }
+        }
+    });
+    case_done
+}
+
+fn linker(engine: &Engine, module: &Module) -> Result<Linker<Host>> {
+    let mut linker = Linker::new(engine);
+    linker.func_wrap(wasm::IMPORT_MODULE, wasm::REPORT_IMPORT, report)?;
+    linker.func_wrap(wasm::IMPORT_MODULE, wasm::CASE_STARTED_IMPORT, case_started)?;
+    // defined with whatever signature the module expects, so unsupported calls fail politely
+    for import in module.imports() {
+        if let (WASI_MODULE, ExternType::Func(ty)) = (import.module(), import.ty()) {
+            let name = import.name().to_owned();
+            linker.func_new(
+                WASI_MODULE,
+                import.name(),
+                ty.clone(),
+                move |caller, params, results| {
+                    let errno = wasi(&name, caller, params)?;
+                    if let Some(result) = results.first_mut() {
+                        *result = Val::I32(errno);
+                    }
+                    Ok(())
+                },
+            )?;
+        }
+    }
+    Ok(linker)
+}
+
+/// The [`wasm::REPORT_IMPORT`] host function, passing the JSON on to the runner's sink
+fn report(caller: Caller<'_, Host>, report_ptr: u32, report_len: u32) -> Result<(), wasmi::Error> {
+    let report = read(&caller, report_ptr, report_len)?;
+    let host = caller.data();
+    if let Some(case_done) = &host.case_done {
+        case_done.send(None).ok();
+    }
+    if let Some((sink, ctx)) = host.report_sink {
+        unsafe { sink(ctx, report.as_ptr(), report.len()) };
+    }
+    Ok(())
+}
+
+/// Re-arms the watchdog with the report to send if this case times out
+fn case_started(
+    caller: Caller<'_, Host>,
+    report_ptr: u32,
+    report_len: u32,
+) -> Result<(), wasmi::Error> {
+    let report = read(&caller, report_ptr, report_len)?;
+    if let Some(case_done) = &caller.data().case_done {
+        case_done.send(Some(report)).ok();
+    }
+    Ok(())
+}
+
+/// The part of WASI that Rust's std uses to print, tell the time and seed hash maps. Anything
+/// else, such as files, fails with `ENOSYS`.
+fn wasi(name: &str, mut caller: Caller<'_, Host>, params: &[Val]) -> Result<i32, wasmi::Error> {
+    let arg = |i: usize| params.get(i).and_then(Val::i32).unwrap_or_default() as u32;
+    match name {
+        "fd_write" => fd_write(&mut caller, arg(0), arg(1), arg(2), arg(3)),
+        "clock_time_get" => {
+            // 0 is the realtime clock, the rest are near enough to monotonic
+            let now = match arg(0) {
+                0 => SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default(),
+                _ => caller.data().started.elapsed(),
+            };
+            write(&mut caller, arg(2), &(now.as_nanos() as u64).to_le_bytes())?;
+            Ok(ERRNO_SUCCESS)
+        }
+        "random_get" => {
+            let random = (0..arg(1).div_ceil(8))
+                .flat_map(|i| RandomState::new().hash_one(i).to_le_bytes())
+                .take(arg(1) as usize)
+                .collect::<Vec<_>>();
+            write(&mut caller, arg(0), &random)?;
+            Ok(ERRNO_SUCCESS)
+        }
+        // no arguments or environment variables
+        "args_sizes_get" | "environ_sizes_get" => {
+            write(&mut caller, arg(0), &0u32.to_le_bytes())?;
+            write(&mut caller, arg(1), &0u32.to_le_bytes())?;
+            Ok(ERRNO_SUCCESS)
+        }
+        "args_get" | "environ_get" | "sched_yield" => Ok(ERRNO_SUCCESS),
+        "proc_exit" => Err(wasmi::Error::i32_exit(arg(0) as i32)),
+        _ => Ok(ERRNO_NOSYS),
+    }
+}
+
+/// Writes the buffers in `iovs` to the runner's stdout or stderr
+fn fd_write(
+    caller: &mut Caller<'_, Host>,
+    fd: u32,
+    iovs: u32,
+    iovs_len: u32,
+    written_ptr: u32,
+) -> Result<i32, wasmi::Error> {
+    let mut bytes = Vec::new();
+    for iov in 0..iovs_len {
+        let iov = read(caller, iovs + iov * 8, 8)?;
+        let ptr = u32::from_le_bytes(iov[..4].try_into().expect("4 bytes"));
+        let len = u32::from_le_bytes(iov[4..].try_into().expect("4 bytes"));
+        bytes.extend(read(caller, ptr, len)?);
+    }
+    let written = match fd {
+        1 => std::io::stdout().write_all(&bytes),
+        2 => std::io::stderr().write_all(&bytes),
+        _ => return Ok(ERRNO_BADF),
+    };
+    if written.is_err() {
+        return Ok(ERRNO_IO);
+    }
+    write(caller, written_ptr, &(bytes.len() as u32).to_le_bytes())?;
+    Ok(ERRNO_SUCCESS)
+}
+
+fn memory(caller: &Caller<'_, Host>) -> Result<wasmi::Memory, wasmi::Error> {
+    caller
+        .get_export("memory")
+        .and_then(Extern::into_memory)
+        .ok_or_else(|| wasmi::Error::new("module doesn't export its memory"))
+}
+
+fn read(caller: &Caller<'_, Host>, ptr: u32, len: u32) -> Result<Vec<u8>, wasmi::Error> {
+    let mut bytes = vec![0; len as usize];
+    memory(caller)?.read(caller, ptr as usize, &mut bytes)?;
+    Ok(bytes)
+}
+
+fn write(caller: &mut Caller<'_, Host>, ptr: u32, bytes: &[u8]) -> Result<(), wasmi::Error> {
+    memory(caller)?.write(caller, ptr as usize, bytes)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reports its input as a case starts and as it finishes, after growing its memory by
+    /// `$pages` and looping `$loops` times
+    fn module(pages: u32, loops: u32) -> Vec<u8> {
+        wat::parse_str(format!(
+            r#"(module
+                (import "aoc" "report" (func $report (param i32 i32)))
+                (import "aoc" "case_started" (func $started (param i32 i32)))
+                (import "wasi_snapshot_preview1" "clock_time_get"
+                    (func $clock (param i32 i64 i32) (result i32)))
+                (import "wasi_snapshot_preview1" "path_open"
+                    (func $open (param i32 i32 i32 i32 i32 i64 i64 i32 i32) (result i32)))
+                (memory (export "memory") 1)
+                (global $next (mut i32) (i32.const 1024))
+                (func (export "aoc_alloc") (param $len i32) (result i32)
+                    (global.get $next)
+                    (global.set $next (i32.add (global.get $next) (local.get $len))))
+                (func (export "aoc_run_cases") (param i32 i32 i32 i32) (result i32)
+                    (local $i i32)
+                    (call $started (local.get 0) (local.get 1))
+                    (if (i32.eq (memory.grow (i32.const {pages})) (i32.const -1))
+                        (then unreachable))
+                    (loop $spin
+                        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+                        (br_if $spin (i32.lt_u (local.get $i) (i32.const {loops}))))
+                    (if (call $clock (i32.const 1) (i64.const 0) (i32.const 0)) (then unreachable))
+                    ;; files are off limits
+                    (if (i32.ne (call $open (i32.const 0) (i32.const 0) (i32.const 0) (i32.const 0)
+                            (i32.const 0) (i64.const 0) (i64.const 0) (i32.const 0) (i32.const 0))
+                            (i32.const {ERRNO_NOSYS}))
+                        (then unreachable))
+                    (call $report (local.get 0) (local.get 1))
+                    (i32.const 1)))"#
+        ))
+        .unwrap()
+    }
+
+    unsafe extern "C" fn collect(ctx: *mut c_void, report_ptr: *const u8, report_len: usize) {
+        let reports = &mut *(ctx as *mut Vec<String>);
+        let report = std::slice::from_raw_parts(report_ptr, report_len);
+        reports.push(String::from_utf8_lossy(report).into_owned());
+    }
+
+    #[test]
+    fn test_run_cases() {
+        let limits = Limits {
+            fuel: Some(100_000),
+            memory_bytes: 2 * MIB,
+            timeout: None,
+            colour: false,
+        };
+        let run = |module: Vec<u8>, limits| {
+            let mut reports: Vec<String> = Vec::new();
+            let sink = (
+                collect as ReportSinkFn,
+                &mut reports as *mut _ as *mut c_void,
+            );
+            let options = WasmRunOptions::default();
+            run_cases(&module, "{}", &options, limits, Some(sink)).map(|passed| (passed, reports))
+        };
+
+        assert_eq!(
+            run(module(1, 1000), limits).unwrap(),
+            (true, vec!["{}".to_owned()])
+        );
+        let error = run(module(1, 1_000_000), limits).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "ran out of fuel after 100000 instructions"
+        );
+        let error = run(module(64, 1), limits).unwrap_err();
+        assert_eq!(error.to_string(), "exceeded the memory limit of 2 MiB");
+        let unlimited = Limits {
+            fuel: None,
+            memory_bytes: 8 * MIB,
+            ..limits
+        };
+        assert!(run(module(64, 1_000_000), unlimited).unwrap().0);
+        let timed = Limits {
+            timeout: Some(Duration::from_secs(60)),
+            ..limits
+        };
+        assert_eq!(
+            run(module(1, 1000), timed).unwrap(),
+            (true, vec!["{}".to_owned()])
+        );
+    }
+}
@@ -0,0 +1,177 @@
+use std::fmt::Display;
+use std::time::{Duration, Instant};
+
+/// Keep sampling until both limits are reached, or the hard cap is hit
+const MIN_RUNS: usize = 5;
+const MIN_DURATION: Duration = Duration::from_secs(1);
+const MAX_RUNS: usize = 10_000;
+/// Comparisons want more samples, as the interval narrows with them
+const MIN_COMPARE_RUNS: usize = 10;
+const MIN_COMPARE_DURATION: Duration = Duration::from_secs(2);
+/// Two-sided 95% quantiles of Student's t for 1 to 30 degrees of freedom, the normal one after
+const T_95: [f64; 30] = [
+    12.71, 4.30, 3.18, 2.78, 2.57, 2.45, 2.36, 2.31, 2.26, 2.23, 2.20, 2.18, 2.16, 2.14, 2.13,
+    2.12, 2.11, 2.10, 2.09, 2.09, 2.08, 2.07, 2.07, 2.06, 2.06, 2.06, 2.05, 2.05, 2.05, 2.04,
+];
+const Z_95: f64 = 1.96;
+
+#[derive(Debug, Copy, Clone)]
+pub struct BenchStats {
+    pub runs: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+}
+
+/// Times repeated calls of `f`, always calling it at least once
+pub fn bench(mut f: impl FnMut()) -> BenchStats {
+    let mut samples = Vec::new();
+    let start = Instant::now();
+
+    while samples.len() < MAX_RUNS && (samples.len() < MIN_RUNS || start.elapsed() < MIN_DURATION) {
+        let run_start = Instant::now();
+        f();
+        samples.push(run_start.elapsed());
+    }
+
+    BenchStats::from_samples(&mut samples)
+}
+
+impl BenchStats {
+    pub fn from_samples(samples: &mut [Duration]) -> Self {
+        assert!(!samples.is_empty(), "no samples");
+        samples.sort_unstable();
+
+        Self {
+            runs: samples.len(),
+            min: samples[0],
+            median: samples[samples.len() / 2],
+            mean: samples.iter().sum::<Duration>() / samples.len() as u32,
+        }
+    }
+}
+
+/// Timings of a baseline and a candidate taken in pairs
+#[derive(Debug, Copy, Clone)]
+pub struct Comparison {
+    pub runs: usize,
+    pub baseline: BenchStats,
+    pub candidate: BenchStats,
+    /// How many times faster the candidate is: the geometric mean of the pairs' ratios
+    pub speedup: f64,
+    /// 95% confidence interval of `speedup`
+    pub low: f64,
+    pub high: f64,
+}
+
+/// Times `baseline` and `candidate` alternately, each returning how long it took, so drift in
+/// the machine's speed affects both alike. Stops at the first error from either.
+pub fn compare<E>(
+    mut baseline: impl FnMut() -> Result<Duration, E>,
+    mut candidate: impl FnMut() -> Result<Duration, E>,
+) -> Result<Comparison, E> {
+    let mut pairs = Vec::new();
+    let start = Instant::now();
+
+    while pairs.len() < MAX_RUNS
+        && (pairs.len() < MIN_COMPARE_RUNS || start.elapsed() < MIN_COMPARE_DURATION)
+    {
+        // neither always goes first, and so always runs on a colder cache
+        let pair = if pairs.len() % 2 == 0 {
+            let baseline = baseline()?;
+            (baseline, candidate()?)
+        } else {
+            let candidate = candidate()?;
+            (baseline()?, candidate)
+        };
+        pairs.push(pair);
+    }
+
+    Ok(Comparison::from_pairs(&pairs))
+}
+
+impl Comparison {
+    pub fn from_pairs(pairs: &[(Duration, Duration)]) -> Self {
+        assert!(pairs.len() >= 2, "too few samples");
+        let nanos = |d: Duration| d.as_nanos().max(1) as f64;
+        let logs = pairs
+            .iter()
+            .map(|&(baseline, candidate)| (nanos(baseline) / nanos(candidate)).ln())
+            .collect::<Vec<_>>();
+        let n = logs.len() as f64;
+        let mean = logs.iter().sum::<f64>() / n;
+        let variance = logs.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0);
+        let t = T_95.get(logs.len() - 2).copied().unwrap_or(Z_95);
+        let margin = t * (variance / n).sqrt();
+
+        let (mut baseline, mut candidate): (Vec<_>, Vec<_>) = pairs.iter().copied().unzip();
+        Self {
+            runs: pairs.len(),
+            baseline: BenchStats::from_samples(&mut baseline),
+            candidate: BenchStats::from_samples(&mut candidate),
+            speedup: mean.exp(),
+            low: (mean - margin).exp(),
+            high: (mean + margin).exp(),
+        }
+    }
+
+    /// Whether the interval excludes no change
+    pub fn is_significant(&self) -> bool {
+        self.low > 1.0 || self.high < 1.0
+    }
+}
+
+impl Display for BenchStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "min {:>10.2?}  median {:>10.2?}  mean {:>10.2?}  ({} runs)",
+            self.min, self.median, self.mean, self.runs
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_comparison() {
+        let ms = Duration::from_millis;
+        let pairs = [
+            (ms(20), ms(10)),
+            (ms(22), ms(10)),
+            (ms(18), ms(10)),
+            (ms(20), ms(10)),
+        ];
+        let comparison = Comparison::from_pairs(&pairs);
+        assert_eq!(comparison.runs, 4);
+        assert!((comparison.speedup - 2.0).abs() < 0.01);
+        assert!(comparison.low < 2.0 && comparison.low > 1.7);
+        assert!(comparison.high > 2.0 && comparison.high < 2.3);
+        assert!(comparison.is_significant());
+        assert_eq!(comparison.candidate.median, ms(10));
+
+        let noisy = Comparison::from_pairs(&[(ms(10), ms(12)), (ms(12), ms(10))]);
+        assert!((noisy.speedup - 1.0).abs() < 1e-9);
+        assert!(!noisy.is_significant());
+    }
+
+    #[test]
+    fn test_compare_error() {
+        let mut runs = 0;
+        let result = compare(
+            || Ok(Duration::from_micros(1)),
+            || {
+                runs += 1;
+                if runs < 3 {
+                    Ok(Duration::from_micros(1))
+                } else {
+                    Err("no answer")
+                }
+            },
+        );
+        assert_eq!(result.err(), Some("no answer"));
+        assert_eq!(runs, 3);
+    }
+}
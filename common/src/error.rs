@@ -0,0 +1,154 @@
+//! Errors for malformed input, pointing back at where in the input they happened
+
+use std::fmt::Display;
+
+/// 1-based position of the offending text, in characters
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Span {
+    pub line: usize,
+    pub column: usize,
+    pub len: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub message: String,
+    pub span: Option<Span>,
+}
+
+pub type ParseResult<T> = Result<T, ParseError>;
+
+/// Why a case produced no answer
+#[derive(Debug, Clone)]
+pub enum CaseError {
+    Parse(ParseError),
+    Panic(String),
+    /// Only for solutions the runner can interrupt, in-process ones end the run instead
+    Timeout(std::time::Duration),
+}
+
+impl ParseError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            span: None,
+        }
+    }
+
+    /// `at` should be a slice of `input` (e.g. from [`crate::lines`] or `split`), otherwise the
+    /// error has no span
+    pub fn at(input: &str, at: &str, message: impl Into<String>) -> Self {
+        let offset = (at.as_ptr() as usize).checked_sub(input.as_ptr() as usize);
+        match offset {
+            Some(offset) if offset + at.len() <= input.len() => {
+                Self::at_offset(input, offset, at.len(), message)
+            }
+            _ => Self::new(message),
+        }
+    }
+
+    /// `offset` and `len` are in bytes
+    pub fn at_offset(input: &str, offset: usize, len: usize, message: impl Into<String>) -> Self {
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+
+        Self {
+            message: message.into(),
+            span: Some(Span {
+                line: before.matches('\n').count() + 1,
+                column: before[line_start..].chars().count() + 1,
+                len: input[offset..offset + len].chars().count().max(1),
+            }),
+        }
+    }
+
+    /// Renders the message with the offending line underlined, like rustc
+    pub fn report(&self, input: &str, input_name: &str) -> String {
+        let Some(span) = self.span else {
+            return self.message.clone();
+        };
+
+        let Some(line) = input.lines().nth(span.line - 1) else {
+            return format!(
+                "{} (at {input_name}:{}:{})",
+                self.message, span.line, span.column
+            );
+        };
+
+        let gutter = " ".repeat(span.line.to_string().len());
+        format!(
+            "{message}\n{gutter}--> {input_name}:{line_no}:{column}\n{gutter} |\n{line_no} | {line}\n{gutter} | {pad}{carets}",
+            message = self.message,
+            line_no = span.line,
+            column = span.column,
+            pad = " ".repeat(span.column - 1),
+            carets = "^".repeat(span.len),
+        )
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.span {
+            Some(span) => write!(f, "{} at {}:{}", self.message, span.line, span.column),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl From<String> for ParseError {
+    fn from(value: String) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<&str> for ParseError {
+    fn from(value: &str) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<std::num::ParseIntError> for ParseError {
+    fn from(value: std::num::ParseIntError) -> Self {
+        Self::new(value.to_string())
+    }
+}
+
+impl From<std::num::ParseFloatError> for ParseError {
+    fn from(value: std::num::ParseFloatError) -> Self {
+        Self::new(value.to_string())
+    }
+}
+
+impl Display for CaseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Parse(e) => e.fmt(f),
+            Self::Panic(msg) => write!(f, "panicked: {msg}"),
+            Self::Timeout(timeout) => write!(f, "timed out after {timeout:?}"),
+        }
+    }
+}
+
+#[test]
+fn test_span() {
+    let input = "abc\nde?f\ngh";
+    let bad = &input[6..7];
+    let err = ParseError::at(input, bad, "bad char");
+
+    assert_eq!(
+        err.span,
+        Some(Span {
+            line: 2,
+            column: 3,
+            len: 1
+        })
+    );
+    assert_eq!(
+        err.report(input, "input"),
+        "bad char\n --> input:2:3\n  |\n2 | de?f\n  |   ^"
+    );
+    assert_eq!(ParseError::at(input, "elsewhere", "nope").span, None);
+}
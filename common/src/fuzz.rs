@@ -0,0 +1,547 @@
+//! Differential fuzzing of a fast implementation against a slow, obviously correct one.
+//!
+//! Generators draw from a [`Gen`], which records each choice. Failing inputs are shrunk by
+//! shrinking those choices rather than the text, so every input tried is still one the generator
+//! can produce.
+
+use crate::{
+    panic_message, CaseError, InputPolicy, MatchPolicy, PartNumber, SolutionResult, SolveFn,
+    SolveOutput,
+};
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::ffi::c_void;
+use std::ops::RangeInclusive;
+
+/// Gives up shrinking after this many attempts, keeping the smallest input so far
+const MAX_SHRINK_ATTEMPTS: usize = 5_000;
+
+/// Source of random choices for a generator. Smaller choices should give smaller inputs, which
+/// the ranges here do by counting up from their start.
+pub struct Gen {
+    choices: Vec<u64>,
+    pos: usize,
+    /// `None` when replaying, where running out of choices gives zeroes
+    rng: Option<SplitMix64>,
+}
+
+impl Gen {
+    fn random(seed: u64) -> Self {
+        Self {
+            choices: Vec::new(),
+            pos: 0,
+            rng: Some(SplitMix64(seed)),
+        }
+    }
+
+    fn replay(choices: Vec<u64>) -> Self {
+        Self {
+            choices,
+            pos: 0,
+            rng: None,
+        }
+    }
+
+    /// A choice below `bound`
+    fn choice(&mut self, bound: u64) -> u64 {
+        let choice = match (self.choices.get(self.pos), &mut self.rng) {
+            (Some(&choice), _) => choice % bound,
+            (None, Some(rng)) => {
+                let choice = rng.next() % bound;
+                self.choices.push(choice);
+                choice
+            }
+            (None, None) => 0,
+        };
+        self.pos += 1;
+        choice
+    }
+
+    pub fn int(&mut self, range: RangeInclusive<i64>) -> i64 {
+        assert!(!range.is_empty(), "Gen::int given an empty range {range:?}");
+        let span = range.end().abs_diff(*range.start()).saturating_add(1);
+        range.start().wrapping_add_unsigned(self.choice(span))
+    }
+
+    pub fn usize(&mut self, range: RangeInclusive<usize>) -> usize {
+        assert!(
+            !range.is_empty(),
+            "Gen::usize given an empty range {range:?}"
+        );
+        let span = (range.end() - range.start()) as u64 + 1;
+        range.start() + self.choice(span) as usize
+    }
+
+    /// Shrinks towards `false`
+    pub fn bool(&mut self) -> bool {
+        self.choice(2) == 1
+    }
+
+    /// Shrinks towards the first item
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        assert!(!items.is_empty(), "Gen::pick given no items");
+        &items[self.usize(0..=items.len() - 1)]
+    }
+}
+
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+}
+
+type GenerateFn = dyn Fn(&mut Gen) -> String;
+
+/// A generator of valid inputs for a part, and two implementations that should always agree
+pub struct FuzzTarget {
+    pub part: PartNumber,
+    generate: Box<GenerateFn>,
+    /// Trusted, so inputs it fails on are skipped rather than reported
+    reference: Box<SolveFn<'static>>,
+    candidate: Box<SolveFn<'static>>,
+}
+
+pub fn fuzz_target<R: SolveOutput, C: SolveOutput>(
+    part: PartNumber,
+    generate: impl Fn(&mut Gen) -> String + 'static,
+    reference: impl Fn(&str) -> R + 'static,
+    candidate: impl Fn(&str) -> C + 'static,
+) -> FuzzTarget {
+    FuzzTarget {
+        part,
+        generate: Box::new(generate),
+        reference: Box::new(move |input| reference(input).into_solve_result()),
+        candidate: Box::new(move |input| candidate(input).into_solve_result()),
+    }
+}
+
+/// How fuzzing a part went, sent to the runner as JSON
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FuzzReport {
+    pub part: u8,
+    pub outcome: FuzzOutcome,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum FuzzOutcome {
+    Passed {
+        tried: u32,
+        /// Inputs the reference implementation failed on
+        skipped: u32,
+    },
+    /// The implementations disagreed on `input`, already shrunk
+    Failed {
+        /// Inputs tried before the first mismatch, including it
+        after: u32,
+        input: String,
+        /// From the reference implementation
+        expected: String,
+        /// The candidate's answer, or its error
+        got: String,
+        /// Rust source for `expected`, as `example_part1` takes it
+        expected_literal: String,
+    },
+    /// The generator itself panicked, say on an empty range, so the target is broken
+    GeneratorPanicked {
+        /// Inputs generated before it, including the one it panicked on
+        after: u32,
+        message: String,
+    },
+}
+
+impl FuzzReport {
+    pub fn decode(bytes: &[u8]) -> serde_json::Result<Self> {
+        serde_json::from_slice(bytes)
+    }
+}
+
+pub type FuzzReportSinkFn =
+    unsafe extern "C" fn(ctx: *mut c_void, report_ptr: *const u8, report_len: usize);
+
+/// Passed by pointer to `fuzz_entrypoint`
+#[repr(C)]
+pub struct FuzzOptions {
+    /// 0 for all parts
+    pub part_filter: u8,
+    /// Inputs to try per target
+    pub iterations: u32,
+    pub seed: u64,
+    pub report_sink: Option<FuzzReportSinkFn>,
+    pub report_ctx: *mut c_void,
+}
+
+pub type FuzzEntrypointFn = unsafe extern "C" fn(options: *const FuzzOptions) -> bool;
+
+/// A mismatch as it was found, as a nondeterministic candidate may not repeat it
+struct Failure {
+    choices: Vec<u64>,
+    input: String,
+    expected: SolutionResult,
+    got: Result<SolutionResult, CaseError>,
+}
+
+enum Verdict {
+    Agree,
+    /// The reference failed, so the input isn't one to judge by
+    Skip,
+    Mismatch {
+        expected: SolutionResult,
+        got: Result<SolutionResult, CaseError>,
+    },
+}
+
+/// How inputs are prepared and answers compared, from the [`crate::Solution`]
+#[derive(Debug, Clone, Copy)]
+pub struct Policies {
+    pub input: InputPolicy,
+    pub matching: MatchPolicy,
+}
+
+impl FuzzTarget {
+    fn check(&self, input: &str, policies: Policies) -> Verdict {
+        let input = policies.input.apply(input);
+        let run = |solve: &SolveFn<'_>| {
+            std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| solve(&input)))
+                .map_err(|payload| CaseError::Panic(panic_message(&*payload)))
+                .and_then(|result| result.map_err(CaseError::Parse))
+        };
+
+        let Ok(expected) = run(&self.reference) else {
+            return Verdict::Skip;
+        };
+        match run(&self.candidate) {
+            Ok(got) if got.matches(&expected, policies.matching) => Verdict::Agree,
+            got => Verdict::Mismatch { expected, got },
+        }
+    }
+
+    /// The generated input, or the generator's panic message
+    fn generate(&self, gen: &mut Gen) -> Result<String, String> {
+        std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| (self.generate)(gen)))
+            .map_err(|payload| panic_message(&*payload))
+    }
+
+    /// Generates from `choices`, returning the input and the choices actually used
+    fn replay(&self, choices: &[u64]) -> Result<(String, Vec<u64>), String> {
+        let mut gen = Gen::replay(choices.to_vec());
+        let input = self.generate(&mut gen)?;
+        let used = gen.pos.min(choices.len());
+        Ok((input, choices[..used].to_vec()))
+    }
+
+    /// The smallest choices found that still give a mismatch: fewer, then lower
+    fn shrink(&self, mut best: Failure, policies: Policies) -> Failure {
+        let attempts = Cell::new(0);
+        let try_choices = |candidate: Vec<u64>, best: &mut Failure| {
+            attempts.set(attempts.get() + 1);
+            if attempts.get() > MAX_SHRINK_ATTEMPTS {
+                return false;
+            }
+            let Ok((input, used)) = self.replay(&candidate) else {
+                return false;
+            };
+            if (used.len(), &used) >= (best.choices.len(), &best.choices) {
+                return false;
+            }
+            match self.check(&input, policies) {
+                Verdict::Mismatch { expected, got } => {
+                    *best = Failure {
+                        choices: used,
+                        input,
+                        expected,
+                        got,
+                    };
+                    true
+                }
+                Verdict::Agree | Verdict::Skip => false,
+            }
+        };
+
+        let mut improved = true;
+        while improved && attempts.get() < MAX_SHRINK_ATTEMPTS {
+            improved = false;
+
+            for chunk in [8, 4, 2, 1] {
+                let mut start = best.choices.len().saturating_sub(chunk);
+                loop {
+                    if start + chunk <= best.choices.len() {
+                        let mut candidate = best.choices.clone();
+                        candidate.drain(start..start + chunk);
+                        improved |= try_choices(candidate, &mut best);
+                    }
+                    if start == 0 {
+                        break;
+                    }
+                    start -= 1;
+                }
+            }
+
+            for i in 0..best.choices.len() {
+                // binary search down towards zero for each choice
+                let mut low = 0;
+                while i < best.choices.len() && low < best.choices[i] {
+                    let mut candidate = best.choices.clone();
+                    candidate[i] = low + (best.choices[i] - low) / 2;
+                    if try_choices(candidate.clone(), &mut best) {
+                        improved = true;
+                    } else {
+                        low = candidate[i] + 1;
+                    }
+                }
+            }
+        }
+
+        best
+    }
+}
+
+/// Fuzzes each target in turn, sending a [`FuzzReport`] for each. Returns whether every target
+/// held up, which is false if there are none.
+pub fn fuzz(targets: &[FuzzTarget], policies: Policies, options: &FuzzOptions) -> bool {
+    let targets = targets
+        .iter()
+        .filter(|target| options.part_filter == 0 || target.part as u8 == options.part_filter)
+        .collect::<Vec<_>>();
+
+    // panics are expected here, and reported with the failing input instead
+    let _quiet = QuietPanics::new();
+
+    let mut all_passed = !targets.is_empty();
+    for (i, target) in targets.iter().enumerate() {
+        let outcome = fuzz_target_once(target, i, policies, options);
+        all_passed &= matches!(outcome, FuzzOutcome::Passed { .. });
+
+        let report = FuzzReport {
+            part: target.part as u8,
+            outcome,
+        };
+        if let Some(sink) = options.report_sink {
+            let encoded = serde_json::to_vec(&report).expect("reports always serialise");
+            unsafe { sink(options.report_ctx, encoded.as_ptr(), encoded.len()) };
+        }
+    }
+
+    all_passed
+}
+
+type PanicHook = Box<dyn Fn(&std::panic::PanicHookInfo<'_>) + Sync + Send>;
+
+/// Silences the panic hook until dropped, even if something panics past it
+struct QuietPanics(Option<PanicHook>);
+
+impl QuietPanics {
+    fn new() -> Self {
+        let hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(|_| {}));
+        Self(Some(hook))
+    }
+}
+
+impl Drop for QuietPanics {
+    fn drop(&mut self) {
+        if let Some(hook) = self.0.take() {
+            std::panic::set_hook(hook);
+        }
+    }
+}
+
+fn fuzz_target_once(
+    target: &FuzzTarget,
+    index: usize,
+    policies: Policies,
+    options: &FuzzOptions,
+) -> FuzzOutcome {
+    let mut skipped = 0;
+    for iteration in 0..options.iterations {
+        let seed = options
+            .seed
+            .wrapping_add((index as u64) << 32)
+            .wrapping_add(iteration as u64);
+        let mut gen = Gen::random(seed);
+        let input = match target.generate(&mut gen) {
+            Ok(input) => input,
+            Err(message) => {
+                return FuzzOutcome::GeneratorPanicked {
+                    after: iteration + 1,
+                    message,
+                }
+            }
+        };
+        match target.check(&input, policies) {
+            Verdict::Agree => {}
+            Verdict::Skip => skipped += 1,
+            Verdict::Mismatch { expected, got } => {
+                let found = Failure {
+                    choices: gen.choices,
+                    input,
+                    expected,
+                    got,
+                };
+                let Failure {
+                    input,
+                    expected,
+                    got,
+                    ..
+                } = target.shrink(found, policies);
+
+                return FuzzOutcome::Failed {
+                    after: iteration + 1,
+                    input,
+                    expected: expected.to_string(),
+                    got: match got {
+                        Ok(got) => got.to_string(),
+                        Err(e) => e.to_string(),
+                    },
+                    expected_literal: expected.literal(),
+                };
+            }
+        }
+    }
+
+    FuzzOutcome::Passed {
+        tried: options.iterations,
+        skipped,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POLICIES: Policies = Policies {
+        input: InputPolicy::TrimEnd,
+        matching: MatchPolicy::Canonical,
+    };
+
+    fn numbers(gen: &mut Gen) -> String {
+        let count = gen.usize(1..=20);
+        (0..count)
+            .map(|_| gen.int(0..=1000).to_string())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn sum(input: &str) -> i64 {
+        input.lines().map(|line| line.parse::<i64>().unwrap()).sum()
+    }
+
+    #[test]
+    fn test_shrink() {
+        // wrong once any number reaches 100
+        let target = fuzz_target(PartNumber::Part1, numbers, sum, |input: &str| {
+            input
+                .lines()
+                .map(|line| line.parse::<i64>().unwrap().min(99))
+                .sum::<i64>()
+        });
+
+        let found = (0..)
+            .find_map(|seed| {
+                let mut gen = Gen::random(seed);
+                let input = target.generate(&mut gen).unwrap();
+                let Verdict::Mismatch { expected, got } = target.check(&input, POLICIES) else {
+                    return None;
+                };
+                Some(Failure {
+                    choices: gen.choices,
+                    input,
+                    expected,
+                    got,
+                })
+            })
+            .unwrap();
+
+        let shrunk = target.shrink(found, POLICIES);
+        assert_eq!(shrunk.input, "100");
+        assert_eq!(target.replay(&shrunk.choices).unwrap().0, "100");
+    }
+
+    #[test]
+    fn test_agreeing_targets() {
+        let target = fuzz_target(PartNumber::Part2, numbers, sum, sum);
+        let options = FuzzOptions {
+            part_filter: 0,
+            iterations: 50,
+            seed: 0,
+            report_sink: None,
+            report_ctx: std::ptr::null_mut(),
+        };
+        assert!(fuzz(&[target], POLICIES, &options));
+    }
+
+    /// Unlike [`str::lines`], chokes on carriage returns
+    fn strict_sum(input: &str) -> i64 {
+        input
+            .split('\n')
+            .map(|line| line.parse::<i64>().unwrap())
+            .sum()
+    }
+
+    #[test]
+    fn test_input_policy() {
+        let target = fuzz_target(
+            PartNumber::Part1,
+            |gen| format!("{}\r\n", numbers(gen).replace('\n', "\r\n")),
+            strict_sum,
+            strict_sum,
+        );
+        let input = target.generate(&mut Gen::random(0)).unwrap();
+        assert!(matches!(target.check(&input, POLICIES), Verdict::Agree));
+        let raw = Policies {
+            input: InputPolicy::Raw,
+            ..POLICIES
+        };
+        assert!(matches!(target.check(&input, raw), Verdict::Skip));
+    }
+
+    #[test]
+    fn test_broken_targets() {
+        let options = FuzzOptions {
+            part_filter: 0,
+            iterations: 50,
+            seed: 0,
+            report_sink: None,
+            report_ctx: std::ptr::null_mut(),
+        };
+        let target = fuzz_target(
+            PartNumber::Part1,
+            |gen| gen.pick::<&str>(&[]).to_string(),
+            sum,
+            sum,
+        );
+        let outcome = fuzz_target_once(&target, 0, POLICIES, &options);
+        assert!(matches!(
+            outcome,
+            FuzzOutcome::GeneratorPanicked { after: 1, message } if message == "Gen::pick given no items"
+        ));
+
+        // disagrees every other time it's asked, so shrinking can't count on a mismatch recurring
+        let calls = Cell::new(0);
+        let flaky = move |input: &str| {
+            calls.set(calls.get() + 1);
+            sum(input) + calls.get() % 2
+        };
+        let target = fuzz_target(PartNumber::Part2, numbers, sum, flaky);
+        assert!(!fuzz(&[target], POLICIES, &options));
+    }
+
+    #[test]
+    fn test_literal() {
+        assert_eq!(SolutionResult::from(480).literal(), "480");
+        assert_eq!(
+            SolutionResult::from(875318608908_i64).literal(),
+            "875318608908"
+        );
+        assert_eq!(
+            SolutionResult::from(u64::MAX).literal(),
+            "SolutionResult::from(18446744073709551615_u64)"
+        );
+        assert_eq!(SolutionResult::from("abc").literal(), "\"abc\"");
+    }
+}
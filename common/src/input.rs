@@ -0,0 +1,81 @@
+use serde::Deserialize;
+use std::borrow::Cow;
+
+/// How an input is tidied before a solution sees it, the same for full inputs and examples
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum InputPolicy {
+    /// Exactly as written
+    Raw,
+    /// `\r\n` becomes `\n`
+    NormaliseLineEndings,
+    /// Line endings normalised, and any whitespace-only lines at the end removed along with the
+    /// last newline. Whitespace within the remaining lines is kept.
+    StripTrailingBlankLines,
+    /// Line endings normalised and trailing whitespace removed
+    #[default]
+    TrimEnd,
+}
+
+impl InputPolicy {
+    pub fn apply(self, input: &str) -> Cow<'_, str> {
+        let input = match self {
+            Self::Raw => return Cow::Borrowed(input),
+            _ if input.contains('\r') => Cow::Owned(input.replace("\r\n", "\n")),
+            _ => Cow::Borrowed(input),
+        };
+
+        let end = match self {
+            Self::Raw | Self::NormaliseLineEndings => return input,
+            Self::StripTrailingBlankLines => {
+                let mut end = input.len();
+                loop {
+                    let (rest, last) = input[..end]
+                        .rsplit_once('\n')
+                        .unwrap_or(("", &input[..end]));
+                    if !last.trim().is_empty() {
+                        break end;
+                    }
+                    if end == 0 {
+                        break 0;
+                    }
+                    end = rest.len();
+                }
+            }
+            Self::TrimEnd => input.trim_end().len(),
+        };
+        match input {
+            Cow::Borrowed(input) => Cow::Borrowed(&input[..end]),
+            Cow::Owned(mut input) => {
+                input.truncate(end);
+                Cow::Owned(input)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply() {
+        let input = "  1 2 \r\n3\r\n \r\n\r\n";
+        assert_eq!(InputPolicy::Raw.apply(input), input);
+        assert_eq!(
+            InputPolicy::NormaliseLineEndings.apply(input),
+            "  1 2 \n3\n \n\n"
+        );
+        assert_eq!(
+            InputPolicy::StripTrailingBlankLines.apply(input),
+            "  1 2 \n3"
+        );
+        assert_eq!(
+            InputPolicy::StripTrailingBlankLines.apply("*   +  \n"),
+            "*   +  "
+        );
+        assert_eq!(InputPolicy::StripTrailingBlankLines.apply(" \n\n"), "");
+        assert_eq!(InputPolicy::TrimEnd.apply(input), "  1 2 \n3");
+        assert_eq!(InputPolicy::TrimEnd.apply("a\n\nb  \n"), "a\n\nb");
+    }
+}
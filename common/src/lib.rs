@@ -1,7 +1,29 @@
+use crate::bench::BenchStats;
+use crate::mismatch::Mismatch;
+use crate::report::{CaseReport, Outcome};
 pub use itertools;
+use itertools::Itertools;
 use owo_colors::OwoColorize;
-use std::borrow::Cow;
-use std::fmt::Display;
+use std::sync::mpsc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+pub mod bench;
+pub mod error;
+pub mod fuzz;
+mod input;
+pub mod mismatch;
+pub mod ocr;
+pub mod progress;
+pub mod report;
+mod result;
+pub mod snippet;
+pub mod viz;
+pub mod wasm;
+
+pub use error::{CaseError, ParseError, ParseResult};
+pub use input::InputPolicy;
+pub use result::{CaseAnswer, MatchPolicy, SolutionResult};
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 #[repr(u8)]
@@ -10,14 +32,94 @@ pub enum PartNumber {
     Part2 = 2,
 }
 
-#[derive(Debug, Clone)]
-pub enum SolutionResult {
-    Int(i64),
-    String(String),
+pub trait Solution {
+    /// How expected answers are compared against results
+    const MATCH_POLICY: MatchPolicy = MatchPolicy::Canonical;
+    /// How inputs and examples are tidied before `solve` sees them
+    const INPUT_POLICY: InputPolicy = InputPolicy::TrimEnd;
+
+    fn solve(input: &str, part: PartNumber) -> impl SolveOutput;
+
+    /// Alternative named implementations, selectable by name or cross-checked against `solve`
+    /// (which is always available as "default").
+    fn implementations() -> Vec<Implementation> {
+        Vec::new()
+    }
+
+    /// Input generators with a reference and candidate implementation, for `aoc fuzz`
+    fn fuzz_targets() -> Vec<fuzz::FuzzTarget> {
+        Vec::new()
+    }
 }
 
-pub trait Solution {
-    fn solve(input: &str, part: PartNumber) -> impl Into<SolutionResult>;
+/// What `solve` can return: an answer, or a `Result` of one for inputs that can fail to parse
+pub trait SolveOutput {
+    fn into_solve_result(self) -> Result<SolutionResult, ParseError>;
+}
+
+impl<T: Into<SolutionResult>> SolveOutput for T {
+    fn into_solve_result(self) -> Result<SolutionResult, ParseError> {
+        Ok(self.into())
+    }
+}
+
+impl<T: Into<SolutionResult>, E: Into<ParseError>> SolveOutput for Result<T, E> {
+    fn into_solve_result(self) -> Result<SolutionResult, ParseError> {
+        self.map(Into::into).map_err(Into::into)
+    }
+}
+
+pub const DEFAULT_IMPLEMENTATION: &str = "default";
+
+pub(crate) type SolveFn<'a> = dyn Fn(&str) -> Result<SolutionResult, ParseError> + 'a;
+
+pub struct Implementation {
+    pub part: PartNumber,
+    pub name: &'static str,
+    solve: Box<SolveFn<'static>>,
+}
+
+impl Implementation {
+    pub fn solve(&self, input: &str) -> Result<SolutionResult, ParseError> {
+        (self.solve)(input)
+    }
+}
+
+pub fn implementation<R: SolveOutput>(
+    part: PartNumber,
+    name: &'static str,
+    solve: impl Fn(&str) -> R + 'static,
+) -> Implementation {
+    assert_ne!(
+        name, DEFAULT_IMPLEMENTATION,
+        "implementation name is reserved"
+    );
+    Implementation {
+        part,
+        name,
+        solve: Box::new(move |input| solve(input).into_solve_result()),
+    }
+}
+
+/// Which implementations to run for each case
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImplSelection {
+    /// Only `Solution::solve`
+    Default,
+    /// A single implementation by name, skipping parts that don't have it
+    Named(String),
+    /// Every implementation for the part, failing the case if they disagree
+    All,
+}
+
+impl ImplSelection {
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "" | DEFAULT_IMPLEMENTATION => Self::Default,
+            "all" => Self::All,
+            name => Self::Named(name.to_owned()),
+        }
+    }
 }
 
 pub enum SolutionInput {
@@ -25,65 +127,273 @@ pub enum SolutionInput {
     Example(&'static str),
 }
 
-pub type CaseEntrypointFn = unsafe extern "C" fn(
-    input_ptr: *const u8,
-    input_len_bytes: usize,
-    part_filter: u8,
-    case_filter: u32,
-    solutions_only: bool,
-) -> bool;
+/// Passed by pointer to `run_cases_entrypoint`, so the host and solution library must be built
+/// from the same `common`.
+#[repr(C)]
+pub struct RunOptions {
+    pub input_ptr: *const u8,
+    pub input_len_bytes: usize,
+    /// 0 for all parts
+    pub part_filter: u8,
+    /// 0 for all cases, otherwise 1-based
+    pub case_filter: u32,
+    pub solutions_only: bool,
+    /// Empty for the default implementation, see [`ImplSelection::parse`]
+    pub impl_ptr: *const u8,
+    pub impl_len_bytes: usize,
+    pub bench: bool,
+    /// Null unless the runner wants [`viz`] frames
+    pub viz_sink: Option<viz::FrameSinkFn>,
+    pub viz_ctx: *mut std::ffi::c_void,
+    /// Only every nth frame is recorded, or built at all, 0 meaning 1
+    pub viz_every: u32,
+    /// Null unless the runner displays [`progress`]
+    pub progress_sink: Option<progress::ProgressSinkFn>,
+    pub progress_ctx: *mut std::ffi::c_void,
+    /// Null unless the runner wants a [`report::CaseReport`] per case
+    pub report_sink: Option<report::ReportSinkFn>,
+    pub report_ctx: *mut std::ffi::c_void,
+    /// Colour is stripped from the output if false
+    pub colour: bool,
+    /// Per case, 0 for none
+    pub timeout_ms: u64,
+    /// The full input differs from the one answers were recorded against
+    pub input_changed: bool,
+}
+
+pub type CaseEntrypointFn = unsafe extern "C" fn(options: *const RunOptions) -> bool;
+
+/// [`RunOptions`] unpacked on the solution side
+#[derive(Debug, Clone)]
+pub struct RunConfig {
+    pub part_filter: Option<PartNumber>,
+    pub case_filter: u32,
+    pub solutions_only: bool,
+    pub implementation: ImplSelection,
+    pub bench: bool,
+    pub viz_sink: Option<(viz::FrameSinkFn, *mut std::ffi::c_void)>,
+    pub viz_every: u32,
+    pub progress_sink: Option<(progress::ProgressSinkFn, *mut std::ffi::c_void)>,
+    pub report_sink: Option<(report::ReportSinkFn, *mut std::ffi::c_void)>,
+    pub colour: bool,
+    /// A case that runs over ends the process, as it can't be interrupted, so the runner only
+    /// sets this in its workers
+    pub timeout: Option<Duration>,
+    /// Explains wrong answers on the full input, which are then likely not bugs
+    pub input_changed: bool,
+}
+
+impl RunConfig {
+    /// # Safety
+    /// `options` must point to a valid [`RunOptions`] whose strings outlive `'a`.
+    pub unsafe fn from_raw<'a>(options: *const RunOptions) -> (&'a str, Self) {
+        let options = &*options;
+        let str_from_raw = |ptr: *const u8, len: usize| {
+            std::str::from_utf8_unchecked(std::slice::from_raw_parts(ptr, len))
+        };
+
+        let input = str_from_raw(options.input_ptr, options.input_len_bytes);
+        let implementation = str_from_raw(options.impl_ptr, options.impl_len_bytes);
+
+        let part_filter = match options.part_filter {
+            0 => None,
+            1 => Some(PartNumber::Part1),
+            2 => Some(PartNumber::Part2),
+            n => panic!("invalid part number {n}"),
+        };
+
+        (
+            input,
+            Self {
+                part_filter,
+                case_filter: options.case_filter,
+                solutions_only: options.solutions_only,
+                implementation: ImplSelection::parse(implementation),
+                bench: options.bench,
+                viz_sink: options.viz_sink.map(|sink| (sink, options.viz_ctx)),
+                viz_every: options.viz_every,
+                progress_sink: options
+                    .progress_sink
+                    .map(|sink| (sink, options.progress_ctx)),
+                report_sink: options.report_sink.map(|sink| (sink, options.report_ctx)),
+                colour: options.colour,
+                timeout: (options.timeout_ms != 0)
+                    .then(|| Duration::from_millis(options.timeout_ms)),
+                input_changed: options.input_changed,
+            },
+        )
+    }
+}
 
 #[macro_export]
 macro_rules! solution {
     ($solution:ty, $cases:expr) => {
         #[no_mangle]
-        pub extern "C" fn run_cases_entrypoint(
-            input_ptr: *const u8,
-            input_len_bytes: usize,
-            part_filter: u8,
-            case_filter: u32,
-            solutions_only: bool,
+        pub unsafe extern "C" fn run_cases_entrypoint(options: *const $crate::RunOptions) -> bool {
+            let (input, config) = unsafe { $crate::RunConfig::from_raw(options) };
+
+            $crate::run_cases::<$solution>(input, &$cases, &config)
+        }
+
+        #[no_mangle]
+        pub unsafe extern "C" fn fuzz_entrypoint(
+            options: *const $crate::fuzz::FuzzOptions,
         ) -> bool {
-            let input = unsafe {
-                std::str::from_utf8_unchecked(std::slice::from_raw_parts(
-                    input_ptr,
-                    input_len_bytes,
-                ))
-            };
+            let options = unsafe { &*options };
+            $crate::fuzz::fuzz(
+                &<$solution as $crate::Solution>::fuzz_targets(),
+                $crate::fuzz::Policies {
+                    input: <$solution as $crate::Solution>::INPUT_POLICY,
+                    matching: <$solution as $crate::Solution>::MATCH_POLICY,
+                },
+                options,
+            )
+        }
 
-            let part = match part_filter {
-                0 => None,
-                1 => Some(common::PartNumber::Part1),
-                2 => Some(common::PartNumber::Part2),
-                _ => panic!("invalid part number {part_filter}"),
-            };
+        #[no_mangle]
+        pub unsafe extern "C" fn snippet_entrypoint(
+            options: *const $crate::snippet::SnippetOptions,
+        ) -> bool {
+            unsafe { $crate::snippet::solve_snippet::<$solution>(options) }
+        }
+
+        #[no_mangle]
+        pub extern "C" fn snippet_abi_version() -> u32 {
+            $crate::snippet::ABI_VERSION
+        }
 
-            $crate::run_cases::<$solution>(input, &$cases, part, case_filter, solutions_only)
+        #[cfg(target_family = "wasm")]
+        #[no_mangle]
+        pub extern "C" fn aoc_alloc(len: usize) -> *mut u8 {
+            $crate::wasm::alloc(len)
         }
+
+        #[cfg(target_family = "wasm")]
+        #[no_mangle]
+        pub unsafe extern "C" fn aoc_run_cases(
+            input_ptr: *const u8,
+            input_len: usize,
+            options_ptr: *const u8,
+            options_len: usize,
+        ) -> bool {
+            let (input, config) =
+                unsafe { $crate::wasm::from_raw(input_ptr, input_len, options_ptr, options_len) };
+
+            $crate::run_cases::<$solution>(input, &$cases, &config)
+        }
+    };
+}
+
+const INPUT_CHANGED: &str = "input changed since answers were recorded";
+
+/// Prints a line from [`run_cases`], without colour if the runner doesn't want it
+macro_rules! say {
+    ($config:expr, $($arg:tt)*) => {
+        println!("{}", paint($config.colour, format!($($arg)*)))
     };
 }
 
 pub fn run_cases<S: Solution>(
     input: &str,
     cases: &[(PartNumber, SolutionInput, Option<SolutionResult>)],
-    part_filter: Option<PartNumber>,
-    case_filter: u32,
-    solutions_only: bool,
+    config: &RunConfig,
+) -> bool {
+    run_case_table(
+        input,
+        cases,
+        &|input, part| {
+            S::solve(input, part)
+                .into_solve_result()
+                .map_err(CaseError::Parse)
+        },
+        &S::implementations(),
+        S::MATCH_POLICY,
+        S::INPUT_POLICY,
+        config,
+    )
+}
+
+pub type PartSolveFn<'a> = dyn Fn(&str, PartNumber) -> Result<SolutionResult, CaseError> + 'a;
+
+type CaseFn<'a> = dyn Fn(&str) -> Result<SolutionResult, CaseError> + 'a;
+
+/// [`run_cases`] without a [`Solution`], for solutions that aren't Rust types (e.g. external
+/// programs driven by the runner)
+pub fn run_case_table(
+    input: &str,
+    cases: &[(PartNumber, SolutionInput, Option<SolutionResult>)],
+    solve: &PartSolveFn<'_>,
+    implementations: &[Implementation],
+    match_policy: MatchPolicy,
+    input_policy: InputPolicy,
+    config: &RunConfig,
 ) -> bool {
     let mut failed = 0;
     let mut total = 0;
     let mut all_passed = true;
 
+    if let ImplSelection::Named(name) = &config.implementation {
+        if !implementations.iter().any(|imp| imp.name == name) {
+            say!(
+                config,
+                "{} no implementation named {name:?}, available: {}",
+                "ERROR".red().bold(),
+                implementations
+                    .iter()
+                    .map(|imp| imp.name)
+                    .dedup()
+                    .join(", ")
+            );
+            return false;
+        }
+    }
+
+    report::set_sink(config.report_sink);
     for (i, (part, case_input, expected)) in cases.iter().enumerate() {
-        if part_filter.is_some() && Some(*part) != part_filter {
+        if config.part_filter.is_some() && Some(*part) != config.part_filter {
+            continue;
+        }
+
+        if config.case_filter != 0 && (i + 1) as u32 != config.case_filter {
             continue;
         }
 
-        if case_filter != 0 && (i + 1) as u32 != case_filter {
+        if config.solutions_only && !matches!(case_input, SolutionInput::FullInput) {
             continue;
         }
 
-        if solutions_only && !matches!(case_input, SolutionInput::FullInput) {
+        let mut candidates: Vec<(&str, Box<CaseFn<'_>>)> = Vec::new();
+        if config.implementation != ImplSelection::Default {
+            candidates.extend(
+                implementations
+                    .iter()
+                    .filter(|imp| imp.part == *part)
+                    .filter(|imp| match &config.implementation {
+                        ImplSelection::Named(name) => imp.name == name,
+                        _ => true,
+                    })
+                    .map(|imp| {
+                        let solve: Box<CaseFn<'_>> =
+                            Box::new(|input| imp.solve(input).map_err(CaseError::Parse));
+                        (imp.name, solve)
+                    }),
+            );
+        }
+        if matches!(
+            config.implementation,
+            ImplSelection::Default | ImplSelection::All
+        ) {
+            candidates.insert(
+                0,
+                (
+                    DEFAULT_IMPLEMENTATION,
+                    Box::new(|input: &str| solve(input, *part)),
+                ),
+            );
+        }
+
+        if candidates.is_empty() {
+            // named implementation only exists for the other part
             continue;
         }
 
@@ -93,48 +403,226 @@ pub fn run_cases<S: Solution>(
             SolutionInput::FullInput => (input, "input  "),
             SolutionInput::Example(example) => (*example, "example"),
         };
-        let result = S::solve(input, *part).into();
+        let input = input_policy.apply(input);
+        let input = &*input;
+
+        let run = |solve: &CaseFn<'_>| {
+            std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| solve(input)))
+                .map_err(|payload| CaseError::Panic(panic_message(&*payload)))?
+        };
+        let case_report = |name: &str, outcome: Outcome| CaseReport {
+            case: (i + 1) as u32,
+            part: *part as u8,
+            full_input: matches!(case_input, SolutionInput::FullInput),
+            implementation: (config.implementation != ImplSelection::Default)
+                .then(|| name.to_owned()),
+            outcome,
+            answer: None,
+            expected: expected.as_ref().map(ToString::to_string),
+            message: None,
+            elapsed_secs: None,
+        };
 
+        viz::set_sink(config.viz_sink, config.viz_every);
+        progress::set_sink(config.progress_sink);
+        let start = Instant::now();
+        let mut elapsed = None;
+        let results = candidates
+            .iter()
+            .map(|(name, solve)| {
+                let _watchdog = config.timeout.map(|timeout| {
+                    let label = match config.implementation {
+                        ImplSelection::Default => String::new(),
+                        _ => format!(" [{name}]"),
+                    };
+                    let message = format!(
+                        "\n{}\n   ✗ {} case #{} for part {part:?} {wat}{label}: exceeded {timeout:?}\n{}",
+                        "═".repeat(80).bright_red(),
+                        "TIMEOUT".red().bold(),
+                        i + 1,
+                        "═".repeat(80).bright_red(),
+                    );
+                    let report = CaseReport {
+                        message: Some(format!("exceeded {timeout:?}")),
+                        elapsed_secs: Some(timeout.as_secs_f64()),
+                        ..case_report(name, Outcome::Timeout)
+                    };
+                    Watchdog::start(timeout, paint(config.colour, message), report)
+                });
+                let result = run(solve);
+                elapsed.get_or_insert_with(|| start.elapsed());
+                (*name, result)
+            })
+            .collect_vec();
+        let elapsed = elapsed.expect("at least one candidate");
+        let elapsed_secs = Some(elapsed.as_secs_f64());
+        let elapsed = format!("{elapsed:.2?}").bright_black().to_string();
+        viz::set_sink(None, 1);
+        progress::set_sink(None);
+
+        if config.bench {
+            let stats = candidates
+                .iter()
+                .zip(&results)
+                .map(|((_, solve), (_, result))| {
+                    result.is_ok().then(|| {
+                        bench::bench(|| {
+                            std::hint::black_box(solve(input)).ok();
+                        })
+                    })
+                })
+                .collect_vec();
+
+            print_bench(config, i, *part, wat, &results, &stats);
+        }
+
+        let (name, result) = &results[0];
+        let label = match config.implementation {
+            ImplSelection::Default => String::new(),
+            _ => format!(" [{name}]"),
+        };
+
+        if let Some((name, Err(error))) = results.iter().find(|(_, result)| result.is_err()) {
+            failed += 1;
+            let label = match config.implementation {
+                ImplSelection::Default => String::new(),
+                _ => format!(" [{name}]"),
+            };
+            let (kind, outcome, message) = match error {
+                CaseError::Parse(e) => ("ERROR", Outcome::Error, e.report(input, wat.trim_end())),
+                CaseError::Panic(msg) => ("PANIC", Outcome::Panic, msg.clone()),
+                CaseError::Timeout(timeout) => {
+                    ("TIMEOUT", Outcome::Timeout, format!("exceeded {timeout:?}"))
+                }
+            };
+            say!(config, "\n{}", "═".repeat(80).bright_red());
+            say!(
+                config,
+                "   ✗ {} case #{} for part {part:?} {wat}{label}: {}",
+                kind.red().bold(),
+                i + 1,
+                message.bright_red()
+            );
+            say!(config, "{}\n", "═".repeat(80).bright_red());
+            // after printing, so the output captured for a case includes its own line
+            report::send(&CaseReport {
+                message: Some(message),
+                elapsed_secs,
+                ..case_report(name, outcome)
+            });
+            all_passed = false;
+            continue;
+        }
+
+        let result = result.as_ref().expect("errors handled above");
+        if let Some((other_name, Ok(other_result))) = results.iter().skip(1).find(|(_, other)| {
+            !other
+                .as_ref()
+                .is_ok_and(|o| o.matches(result, match_policy))
+        }) {
+            failed += 1;
+            say!(config, "\n{}", "═".repeat(80).bright_red());
+            say!(
+                config,
+                "   ✗ {} case #{} for part {part:?} {wat}: implementations disagree",
+                "FAIL".red().bold(),
+                i + 1,
+            );
+            for (name, result) in [(name, result), (other_name, other_result)] {
+                say!(config, "      {name:<20} {}", result.bright_red().bold());
+            }
+            say!(config, "{}\n", "═".repeat(80).bright_red());
+            report::send(&CaseReport {
+                answer: Some(result.to_string()),
+                message: Some(format!("{other_name} gave {other_result}")),
+                elapsed_secs,
+                ..case_report(name, Outcome::Disagreement)
+            });
+            all_passed = false;
+            continue;
+        }
+
+        let result = result.clone();
+        let outcome = match expected {
+            Some(expected) if expected.matches(&result, match_policy) => Outcome::Pass,
+            Some(_) => Outcome::WrongAnswer,
+            None => Outcome::Unchecked,
+        };
+        let input_changed = outcome == Outcome::WrongAnswer
+            && config.input_changed
+            && matches!(case_input, SolutionInput::FullInput);
+        let mismatch = match &expected {
+            Some(expected) if outcome == Outcome::WrongAnswer => {
+                Mismatch::between(expected, &result)
+            }
+            _ => None,
+        };
         match expected.clone() {
-            Some(expected) if expected == result => {
-                println!("\n{}", "═".repeat(80).bright_blue());
-                println!(
-                    "   {} {} case #{} for part {part:?} {wat}: {}",
-                    "✓",
+            Some(expected) if expected.matches(&result, match_policy) => {
+                say!(config, "\n{}", "═".repeat(80).bright_blue());
+                say!(
+                    config,
+                    "   ✓ {} case #{} for part {part:?} {wat}{label}: {}  {elapsed}",
                     "PASS".green().bold(),
                     i + 1,
                     expected.bright_green().bold()
                 );
-                println!("{}\n", "═".repeat(80).bright_blue());
+                say!(config, "{}\n", "═".repeat(80).bright_blue());
             }
             Some(expected) => {
                 failed += 1;
-                println!("\n{}", "═".repeat(80).bright_red());
-                println!(
-                    "   {} {} case #{} for part {part:?} {wat}: expected {}, got {}",
-                    "✗",
+                say!(config, "\n{}", "═".repeat(80).bright_red());
+                say!(
+                    config,
+                    "   ✗ {} case #{} for part {part:?} {wat}{label}: expected {}, got {}  {elapsed}",
                     "FAIL".red().bold(),
                     i + 1,
                     expected.bright_yellow().bold(),
                     result.bright_red().bold()
                 );
-                println!("{}\n", "═".repeat(80).bright_red());
+                if let Some(mismatch) = &mismatch {
+                    for line in mismatch.explain() {
+                        say!(config, "     {line}");
+                    }
+                }
+                if input_changed {
+                    say!(
+                        config,
+                        "     {}",
+                        format!("{INPUT_CHANGED}, so this may not be a bug").bright_yellow()
+                    );
+                }
+                say!(config, "{}\n", "═".repeat(80).bright_red());
                 all_passed = false;
             }
             None => {
-                println!(
-                    "{} {} case #{} for part {part:?} {wat}: {}",
-                    "?",
+                say!(
+                    config,
+                    "? {} case #{} for part {part:?} {wat}{label}: {}  {elapsed}",
                     "INFO".bright_yellow(),
                     i + 1,
                     result.bright_white()
                 );
             }
         }
+        report::send(&CaseReport {
+            answer: Some(result.to_string()),
+            message: mismatch
+                .as_ref()
+                .map(Mismatch::summary)
+                .into_iter()
+                .chain(input_changed.then(|| INPUT_CHANGED.to_owned()))
+                .reduce(|a, b| format!("{a}; {b}")),
+            elapsed_secs,
+            ..case_report(name, outcome)
+        });
     }
 
+    report::set_sink(None);
+
     if total > 0 {
-        println!(
+        say!(
+            config,
             "\n{} {} of {} tests passed",
             "Results:".bold(),
             (total - failed).green(),
@@ -145,6 +633,128 @@ pub fn run_cases<S: Solution>(
     all_passed
 }
 
+/// Ends the process unless dropped within the timeout
+struct Watchdog {
+    disarm: Option<mpsc::Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Watchdog {
+    /// `report` is sent to the runner before exiting, so it can still write out results
+    #[cfg(not(target_family = "wasm"))]
+    fn start(timeout: Duration, message: String, report: CaseReport) -> Self {
+        let (disarm, disarmed) = mpsc::channel::<()>();
+        let thread = std::thread::spawn(move || {
+            if let Err(mpsc::RecvTimeoutError::Timeout) = disarmed.recv_timeout(timeout) {
+                println!("{message}");
+                report::send(&report);
+                std::process::exit(124);
+            }
+        });
+
+        Self {
+            disarm: Some(disarm),
+            thread: Some(thread),
+        }
+    }
+}
+
+impl Watchdog {
+    /// The host times the case instead, and sends `report` itself
+    #[cfg(target_family = "wasm")]
+    fn start(_timeout: Duration, _message: String, report: CaseReport) -> Self {
+        wasm::case_started(&report);
+        Self {
+            disarm: None,
+            thread: None,
+        }
+    }
+}
+
+impl Drop for Watchdog {
+    fn drop(&mut self) {
+        // joined so the thread is gone before the library can be unloaded
+        drop(self.disarm.take());
+        if let Some(thread) = self.thread.take() {
+            thread.join().ok();
+        }
+    }
+}
+
+fn paint(colour: bool, text: String) -> String {
+    if colour {
+        text
+    } else {
+        strip_ansi(&text)
+    }
+}
+
+/// Removes colour and style escape sequences
+pub fn strip_ansi(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // CSI sequences end with a byte in @..=~
+            chars.by_ref().skip(1).find(|c| ('@'..='~').contains(c));
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else {
+        "unknown panic payload".to_owned()
+    }
+}
+
+fn print_bench(
+    config: &RunConfig,
+    case_idx: usize,
+    part: PartNumber,
+    wat: &str,
+    results: &[(&str, Result<SolutionResult, CaseError>)],
+    stats: &[Option<BenchStats>],
+) {
+    say!(
+        config,
+        "\n⏱ {} case #{} for part {part:?} {wat}",
+        "BENCH".bright_cyan().bold(),
+        case_idx + 1
+    );
+
+    let fastest = stats
+        .iter()
+        .flatten()
+        .map(|stats| stats.median)
+        .min()
+        .unwrap_or_default();
+
+    for ((name, result), stats) in results.iter().zip(stats) {
+        match (result, stats) {
+            (Ok(result), Some(stats)) => {
+                let relative = stats.median.as_secs_f64() / fastest.as_secs_f64().max(f64::EPSILON);
+                say!(
+                    config,
+                    "   {name:<20} {stats}  {:>6.2}x  => {}",
+                    relative,
+                    result.bright_white()
+                );
+            }
+            (Err(e), _) => say!(config, "   {name:<20} {}", e.bright_red()),
+            (Ok(_), None) => unreachable!("successful results are always benchmarked"),
+        }
+    }
+}
+
+/// Non-empty lines, trimmed. Solutions where whitespace matters want `str::lines` and an
+/// [`InputPolicy`] that keeps it.
 pub fn lines(input: &str) -> impl Iterator<Item = &str> {
     input
         .lines()
@@ -153,7 +763,7 @@ pub fn lines(input: &str) -> impl Iterator<Item = &str> {
 }
 
 pub fn solution_part1(
-    answer: Option<impl Into<SolutionResult>>,
+    answer: Option<impl CaseAnswer>,
 ) -> (PartNumber, SolutionInput, Option<SolutionResult>) {
     (
         PartNumber::Part1,
@@ -163,7 +773,7 @@ pub fn solution_part1(
 }
 
 pub fn solution_part2(
-    answer: Option<impl Into<SolutionResult>>,
+    answer: Option<impl CaseAnswer>,
 ) -> (PartNumber, SolutionInput, Option<SolutionResult>) {
     (
         PartNumber::Part2,
@@ -173,7 +783,7 @@ pub fn solution_part2(
 }
 
 pub fn example_part1(
-    answer: impl Into<SolutionResult>,
+    answer: impl CaseAnswer,
     input: &'static str,
 ) -> (PartNumber, SolutionInput, Option<SolutionResult>) {
     (
@@ -184,7 +794,7 @@ pub fn example_part1(
 }
 
 pub fn example_part2(
-    answer: impl Into<SolutionResult>,
+    answer: impl CaseAnswer,
     input: &'static str,
 ) -> (PartNumber, SolutionInput, Option<SolutionResult>) {
     (
@@ -194,56 +804,112 @@ pub fn example_part2(
     )
 }
 
-impl From<i64> for SolutionResult {
-    fn from(value: i64) -> Self {
-        Self::Int(value)
-    }
+pub mod prelude {
+    pub use crate::{
+        example_part1, example_part2, fuzz::fuzz_target, fuzz::FuzzTarget, fuzz::Gen,
+        implementation, lines, solution, solution_part1, solution_part2, Implementation,
+        InputPolicy, MatchPolicy, ParseError, ParseResult, PartNumber, Solution, SolutionInput,
+        SolutionResult, SolveOutput,
+    };
+
+    pub use crate::ocr::LitGrid;
+    pub use itertools::{self, Itertools};
 }
 
-impl From<String> for SolutionResult {
-    fn from(value: String) -> Self {
-        Self::String(value)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_ansi() {
+        let text = format!(
+            "{} case #{}: {}",
+            "PASS".green().bold(),
+            1,
+            "42".bright_white()
+        );
+        assert_eq!(strip_ansi(&text), "PASS case #1: 42");
     }
-}
 
-impl From<&str> for SolutionResult {
-    fn from(value: &str) -> Self {
-        Self::String(value.to_owned())
+    unsafe extern "C" fn collect(ctx: *mut std::ffi::c_void, ptr: *const u8, len: usize) {
+        let reports = &mut *(ctx as *mut Vec<CaseReport>);
+        reports.push(CaseReport::decode(std::slice::from_raw_parts(ptr, len)).unwrap());
     }
-}
 
-impl PartialEq<Self> for SolutionResult {
-    fn eq(&self, other: &Self) -> bool {
-        let a = match self {
-            Self::Int(i) => Cow::Owned(i.to_string()),
-            Self::String(s) => Cow::Borrowed(s),
+    #[test]
+    fn test_implementations() {
+        let number = |input: &str| input.parse::<i64>().unwrap();
+        let times = |name, factor| {
+            implementation(PartNumber::Part1, name, move |input| number(input) * factor)
         };
-
-        let b = match other {
-            Self::Int(i) => Cow::Owned(i.to_string()),
-            Self::String(s) => Cow::Borrowed(s),
+        let cases = [(
+            PartNumber::Part1,
+            SolutionInput::Example("3"),
+            Some(SolutionResult::from(6)),
+        )];
+
+        // one test, as the report sink is global
+        let run = |implementations: &[Implementation], selection: &str| {
+            let mut reports: Vec<CaseReport> = Vec::new();
+            let config = RunConfig {
+                part_filter: None,
+                case_filter: 0,
+                solutions_only: false,
+                implementation: ImplSelection::parse(selection),
+                bench: false,
+                viz_sink: None,
+                viz_every: 1,
+                progress_sink: None,
+                report_sink: Some((collect, &mut reports as *mut _ as *mut std::ffi::c_void)),
+                colour: false,
+                timeout: None,
+                input_changed: false,
+            };
+            let passed = run_case_table(
+                "",
+                &cases,
+                &|input, _| Ok(SolutionResult::from(number(input) * 2)),
+                implementations,
+                MatchPolicy::Canonical,
+                InputPolicy::TrimEnd,
+                &config,
+            );
+            (passed, reports)
+        };
+        let outcomes = |reports: &[CaseReport]| {
+            reports
+                .iter()
+                .map(|report| (report.implementation.clone(), report.outcome))
+                .collect_vec()
         };
 
-        a == b
-    }
-}
-
-impl Eq for SolutionResult {}
+        let (passed, reports) = run(&[times("double", 2), times("triple", 3)], "double");
+        assert!(passed);
+        assert_eq!(
+            outcomes(&reports),
+            [(Some("double".to_owned()), Outcome::Pass)]
+        );
 
-impl Display for SolutionResult {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Self::Int(i) => i.fmt(f),
-            Self::String(s) => s.fmt(f),
-        }
-    }
-}
+        let (passed, reports) = run(&[times("double", 2), times("triple", 3)], "nope");
+        assert!(!passed);
+        assert!(reports.is_empty());
 
-pub mod prelude {
-    pub use crate::{
-        example_part1, example_part2, lines, solution, solution_part1, solution_part2, PartNumber,
-        Solution, SolutionInput, SolutionResult,
-    };
+        let (passed, reports) = run(&[times("double", 2), times("doubled-again", 2)], "all");
+        assert!(passed);
+        assert_eq!(
+            outcomes(&reports),
+            [(Some(DEFAULT_IMPLEMENTATION.to_owned()), Outcome::Pass)]
+        );
 
-    pub use itertools::{self, Itertools};
+        let (passed, reports) = run(&[times("double", 2), times("triple", 3)], "all");
+        assert!(!passed);
+        assert_eq!(
+            outcomes(&reports),
+            [(
+                Some(DEFAULT_IMPLEMENTATION.to_owned()),
+                Outcome::Disagreement
+            )]
+        );
+        assert_eq!(reports[0].message.as_deref(), Some("triple gave 9"));
+    }
 }
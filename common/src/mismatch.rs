@@ -0,0 +1,320 @@
+//! Explains how a wrong answer differs from the expected one
+
+use crate::result::SolutionResult;
+use num_bigint::{BigInt, Sign};
+use owo_colors::OwoColorize;
+
+/// Token diffs larger than this fall back to one change between the common prefix and suffix
+const MAX_DIFF_CELLS: usize = 1 << 20;
+/// Lines of a multi-line diff shown before the rest are only counted
+const MAX_DIFF_LINES: usize = 5;
+
+/// How a wrong answer differs from the expected one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mismatch {
+    /// `got - expected`, when both are numbers
+    Numeric { delta: BigInt },
+    /// Anything else compares as text. `line` and `column` are 1-based, in characters.
+    Text {
+        line: usize,
+        column: usize,
+        multi_line: bool,
+        diff: Vec<Edit>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Edit {
+    Same(String),
+    /// Only in the expected answer
+    Removed(String),
+    /// Only in the result
+    Added(String),
+}
+
+impl Mismatch {
+    /// `None` if they don't actually differ
+    pub fn between(expected: &SolutionResult, got: &SolutionResult) -> Option<Self> {
+        let (expected, got) = (expected.compare_text(), got.compare_text());
+        // a delta of 0 means they only differ in formatting, like "05" and 5
+        if let (Ok(expected), Ok(got)) = (expected.parse::<BigInt>(), got.parse::<BigInt>()) {
+            let delta = got - expected;
+            if delta.sign() != Sign::NoSign {
+                return Some(Self::Numeric { delta });
+            }
+        }
+        if expected == got {
+            return None;
+        }
+        let divergence = expected
+            .chars()
+            .zip(got.chars())
+            .take_while(|(a, b)| a == b)
+            .count();
+        let before = got.chars().take(divergence).collect::<String>();
+        let line = before.matches('\n').count() + 1;
+        let column = before
+            .rsplit('\n')
+            .next()
+            .unwrap_or_default()
+            .chars()
+            .count()
+            + 1;
+
+        Some(Self::Text {
+            line,
+            column,
+            multi_line: expected.contains('\n') || got.contains('\n'),
+            diff: diff(&expected, &got),
+        })
+    }
+
+    /// One line, e.g. "too high by 12" or "differs at character 9"
+    pub fn summary(&self) -> String {
+        match self {
+            Self::Numeric { delta } => match delta.sign() {
+                Sign::Minus => format!("too low by {}", delta.magnitude()),
+                _ => format!("too high by {}", delta.magnitude()),
+            },
+            Self::Text {
+                line,
+                column,
+                multi_line: true,
+                ..
+            } => format!("differs at line {line}, column {column}"),
+            Self::Text { column, .. } => format!("differs at character {column}"),
+        }
+    }
+
+    /// The summary, then for text a diff with removals as `[-x-]` and additions as `{+y+}`,
+    /// coloured. Single-line diffs get a caret under the first change, multi-line ones show only
+    /// the lines that changed.
+    pub fn explain(&self) -> Vec<String> {
+        let mut lines = vec![self.summary()];
+        let Self::Text {
+            diff, multi_line, ..
+        } = self
+        else {
+            return lines;
+        };
+
+        let rendered = render_lines(diff);
+        if !multi_line {
+            let (text, caret) = &rendered[0];
+            lines.push(text.clone());
+            lines.push(format!("{}^", " ".repeat(caret.unwrap_or_default())));
+            return lines;
+        }
+
+        let width = rendered.len().to_string().len();
+        let changed = rendered
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, caret))| caret.is_some())
+            .collect::<Vec<_>>();
+        for (n, (text, _)) in changed.iter().take(MAX_DIFF_LINES) {
+            lines.push(format!("{:>width$} | {text}", n + 1));
+        }
+        if changed.len() > MAX_DIFF_LINES {
+            lines.push(format!(
+                "and {} more lines differ",
+                changed.len() - MAX_DIFF_LINES
+            ));
+        }
+        lines
+    }
+}
+
+/// Each line of the diff, with the column of its first change if it has one
+fn render_lines(diff: &[Edit]) -> Vec<(String, Option<usize>)> {
+    let mut lines = vec![(String::new(), None)];
+    for edit in diff {
+        let (text, mark): (&str, fn(&str) -> String) = match edit {
+            Edit::Same(text) => (text, |s| s.to_owned()),
+            Edit::Removed(text) => (text, |s| format!("[-{s}-]").red().to_string()),
+            Edit::Added(text) => (text, |s| format!("{{+{s}+}}").green().to_string()),
+        };
+        for (i, part) in text.split('\n').enumerate() {
+            if i > 0 {
+                lines.push((String::new(), None));
+            }
+            if part.is_empty() {
+                continue;
+            }
+            let (line, caret) = lines.last_mut().unwrap();
+            if !matches!(edit, Edit::Same(_)) && caret.is_none() {
+                *caret = Some(crate::strip_ansi(line).chars().count());
+            }
+            line.push_str(&mark(part));
+        }
+    }
+    lines
+}
+
+/// Words and numbers stay whole, so a wrong number in a list is one change. Answers that are one
+/// word are compared by character instead.
+fn tokens(text: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        if !c.is_alphanumeric() {
+            if start < i {
+                tokens.push(&text[start..i]);
+            }
+            tokens.push(&text[i..i + c.len_utf8()]);
+            start = i + c.len_utf8();
+        }
+    }
+    if start < text.len() {
+        tokens.push(&text[start..]);
+    }
+    tokens
+}
+
+fn chars(text: &str) -> Vec<&str> {
+    text.char_indices()
+        .map(|(i, c)| &text[i..i + c.len_utf8()])
+        .collect()
+}
+
+/// Longest-common-subsequence diff of the tokens, with adjacent edits of a kind merged
+fn diff(expected: &str, got: &str) -> Vec<Edit> {
+    let (mut a, mut b) = (tokens(expected), tokens(got));
+    if a.len() <= 1 && b.len() <= 1 {
+        (a, b) = (chars(expected), chars(got));
+    }
+
+    let prefix = a.iter().zip(&b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+    let (middle_a, middle_b) = (&a[prefix..a.len() - suffix], &b[prefix..b.len() - suffix]);
+
+    let mut edits = Vec::new();
+    edits.extend(a[..prefix].iter().map(|t| Edit::Same(t.to_string())));
+    if middle_a.len() * middle_b.len() > MAX_DIFF_CELLS {
+        edits.push(Edit::Removed(middle_a.concat()));
+        edits.push(Edit::Added(middle_b.concat()));
+    } else {
+        edits.extend(lcs_diff(middle_a, middle_b));
+    }
+    edits.extend(
+        a[a.len() - suffix..]
+            .iter()
+            .map(|t| Edit::Same(t.to_string())),
+    );
+
+    let mut merged: Vec<Edit> = Vec::new();
+    for edit in edits {
+        match (merged.last_mut(), edit) {
+            (Some(Edit::Same(last)), Edit::Same(text))
+            | (Some(Edit::Removed(last)), Edit::Removed(text))
+            | (Some(Edit::Added(last)), Edit::Added(text)) => last.push_str(&text),
+            (_, edit) => merged.push(edit),
+        }
+    }
+    merged
+}
+
+fn lcs_diff(a: &[&str], b: &[&str]) -> Vec<Edit> {
+    // lengths[i][j] is the LCS of a[i..] and b[j..]
+    let mut lengths = vec![vec![0_u32; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lengths[i][j] = if a[i] == b[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut edits = Vec::new();
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            edits.push(Edit::Same(a[i].to_owned()));
+            (i, j) = (i + 1, j + 1);
+        } else if i < a.len() && (j == b.len() || lengths[i + 1][j] >= lengths[i][j + 1]) {
+            edits.push(Edit::Removed(a[i].to_owned()));
+            i += 1;
+        } else {
+            edits.push(Edit::Added(b[j].to_owned()));
+            j += 1;
+        }
+    }
+
+    // removals read better before the additions that replace them
+    for k in 1..edits.len() {
+        let mut k = k;
+        while k > 0
+            && matches!(edits[k], Edit::Removed(_))
+            && matches!(edits[k - 1], Edit::Added(_))
+        {
+            edits.swap(k, k - 1);
+            k -= 1;
+        }
+    }
+    edits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strip_ansi;
+
+    fn explain(expected: &str, got: &str) -> Vec<String> {
+        Mismatch::between(&expected.into(), &got.into())
+            .unwrap()
+            .explain()
+            .iter()
+            .map(|line| strip_ansi(line))
+            .collect()
+    }
+
+    #[test]
+    fn test_numeric() {
+        let mismatch = Mismatch::between(&48.into(), &12_u64.into()).unwrap();
+        assert_eq!(mismatch.summary(), "too low by 36");
+        let mismatch = Mismatch::between(&"48".into(), &50.into()).unwrap();
+        assert_eq!(mismatch.summary(), "too high by 2");
+        assert_eq!(Mismatch::between(&5.into(), &5_u8.into()), None);
+        assert_eq!(
+            Mismatch::between(&"05".into(), &5.into())
+                .unwrap()
+                .summary(),
+            "differs at character 1"
+        );
+    }
+
+    #[test]
+    fn test_text() {
+        assert_eq!(
+            explain("3,5,0,1,5,1,5,1,0", "3,5,0,1,7,1,5,1,0"),
+            [
+                "differs at character 9",
+                "3,5,0,1,[-5-]{+7+},1,5,1,0",
+                "        ^"
+            ]
+        );
+        assert_eq!(
+            explain("aaa,bbb,ccc", "aaa,ccc,ddd"),
+            ["differs at character 5", "aaa,[-bbb,-]ccc{+,ddd+}", "    ^"]
+        );
+        assert_eq!(
+            explain("ABCDEFGH", "ABCXEFGH"),
+            ["differs at character 4", "ABC[-D-]{+X+}EFGH", "   ^"]
+        );
+        assert_eq!(
+            explain("a b\nc d\ne f", "a b\nc x\ne f\ng"),
+            [
+                "differs at line 2, column 3",
+                "2 | c [-d-]{+x+}",
+                "4 | {+g+}"
+            ]
+        );
+    }
+}
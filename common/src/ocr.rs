@@ -0,0 +1,278 @@
+//! Recognises answers drawn as block letters, in the two fonts the puzzles use
+
+const SMALL_FONT_HEIGHT: usize = 6;
+const LARGE_FONT_HEIGHT: usize = 10;
+
+/// 4x6 glyphs (some letters are narrower or wider)
+const SMALL_FONT: &[(char, &str)] = &[
+    ('A', ".##.\n#..#\n#..#\n####\n#..#\n#..#"),
+    ('B', "###.\n#..#\n###.\n#..#\n#..#\n###."),
+    ('C', ".##.\n#..#\n#...\n#...\n#..#\n.##."),
+    ('E', "####\n#...\n###.\n#...\n#...\n####"),
+    ('F', "####\n#...\n###.\n#...\n#...\n#..."),
+    ('G', ".##.\n#..#\n#...\n#.##\n#..#\n.###"),
+    ('H', "#..#\n#..#\n####\n#..#\n#..#\n#..#"),
+    ('I', "###\n.#.\n.#.\n.#.\n.#.\n###"),
+    ('J', "..##\n...#\n...#\n...#\n#..#\n.##."),
+    ('K', "#..#\n#.#.\n##..\n#.#.\n#.#.\n#..#"),
+    ('L', "#...\n#...\n#...\n#...\n#...\n####"),
+    ('O', ".##.\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('P', "###.\n#..#\n#..#\n###.\n#...\n#..."),
+    ('R', "###.\n#..#\n#..#\n###.\n#.#.\n#..#"),
+    ('S', ".###\n#...\n#...\n.##.\n...#\n###."),
+    ('U', "#..#\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('Y', "#...#\n#...#\n.#.#.\n..#..\n..#..\n..#.."),
+    ('Z', "####\n...#\n..#.\n.#..\n#...\n####"),
+];
+
+/// 6x10 glyphs
+const LARGE_FONT: &[(char, &str)] = &[
+    (
+        'A',
+        "..##..\n.#..#.\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#",
+    ),
+    (
+        'B',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#....#\n#....#\n#....#\n#....#\n#####.",
+    ),
+    (
+        'C',
+        ".####.\n#....#\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#....#\n.####.",
+    ),
+    (
+        'E',
+        "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n######",
+    ),
+    (
+        'F',
+        "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n#.....",
+    ),
+    (
+        'G',
+        ".####.\n#....#\n#.....\n#.....\n#.....\n#..###\n#....#\n#....#\n#...##\n.###.#",
+    ),
+    (
+        'H',
+        "#....#\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#\n#....#",
+    ),
+    (
+        'J',
+        "...###\n....#.\n....#.\n....#.\n....#.\n....#.\n....#.\n#...#.\n#...#.\n.###..",
+    ),
+    (
+        'K',
+        "#....#\n#...#.\n#..#..\n#.#...\n##....\n##....\n#.#...\n#..#..\n#...#.\n#....#",
+    ),
+    (
+        'L',
+        "#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n######",
+    ),
+    (
+        'N',
+        "#....#\n##...#\n##...#\n#.#..#\n#.#..#\n#..#.#\n#..#.#\n#...##\n#...##\n#....#",
+    ),
+    (
+        'P',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#.....\n#.....\n#.....\n#.....\n#.....",
+    ),
+    (
+        'R',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#..#..\n#...#.\n#...#.\n#....#\n#....#",
+    ),
+    (
+        'X',
+        "#....#\n#....#\n.#..#.\n.#..#.\n..##..\n..##..\n.#..#.\n.#..#.\n#....#\n#....#",
+    ),
+    (
+        'Z',
+        "######\n.....#\n.....#\n....#.\n...#..\n..#...\n.#....\n#.....\n#.....\n######",
+    ),
+];
+
+/// A grid of lit and unlit cells, as drawn by a solution
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LitGrid {
+    rows: Vec<Vec<bool>>,
+}
+
+impl LitGrid {
+    pub fn new(width: usize, height: usize, lit: impl Fn(usize, usize) -> bool) -> Self {
+        Self {
+            rows: (0..height)
+                .map(|y| (0..width).map(|x| lit(x, y)).collect())
+                .collect(),
+        }
+    }
+
+    pub fn from_rows(rows: Vec<Vec<bool>>) -> Self {
+        Self { rows }
+    }
+
+    /// `#` is lit, anything else (usually `.` or space) is not
+    pub fn parse(text: &str) -> Self {
+        Self {
+            rows: text
+                .lines()
+                .map(|line| line.chars().map(|c| c == '#').collect())
+                .collect(),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.rows.iter().map(Vec::len).max().unwrap_or(0)
+    }
+
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_lit(&self, x: usize, y: usize) -> bool {
+        self.rows
+            .get(y)
+            .and_then(|row| row.get(x))
+            .copied()
+            .unwrap_or(false)
+    }
+
+    /// Drops blank rows and columns around the edges
+    fn trimmed(&self) -> Self {
+        let lit_rows = (0..self.height())
+            .filter(|&y| self.rows[y].iter().any(|&b| b))
+            .collect::<Vec<_>>();
+        let lit_cols = (0..self.width())
+            .filter(|&x| (0..self.height()).any(|y| self.is_lit(x, y)))
+            .collect::<Vec<_>>();
+
+        let (Some(&top), Some(&bottom), Some(&left), Some(&right)) = (
+            lit_rows.first(),
+            lit_rows.last(),
+            lit_cols.first(),
+            lit_cols.last(),
+        ) else {
+            return Self { rows: vec![] };
+        };
+
+        Self::new(right - left + 1, bottom - top + 1, |x, y| {
+            self.is_lit(left + x, top + y)
+        })
+    }
+
+    /// Splits on fully blank columns
+    fn glyphs(&self) -> Vec<Self> {
+        let mut glyphs = vec![];
+        let mut start = None;
+
+        for x in 0..=self.width() {
+            let blank = (0..self.height()).all(|y| !self.is_lit(x, y));
+            match (start, blank) {
+                (None, false) => start = Some(x),
+                (Some(from), true) => {
+                    glyphs.push(Self::new(x - from, self.height(), |gx, y| {
+                        self.is_lit(from + gx, y)
+                    }));
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+
+        glyphs
+    }
+
+    /// Recognised letters, or `None` if the height doesn't match a font or any glyph is unknown
+    pub fn recognise(&self) -> Option<String> {
+        let trimmed = self.trimmed();
+        let font = match trimmed.height() {
+            SMALL_FONT_HEIGHT => SMALL_FONT,
+            LARGE_FONT_HEIGHT => LARGE_FONT,
+            _ => return None,
+        };
+
+        trimmed
+            .glyphs()
+            .iter()
+            .map(|glyph| {
+                font.iter()
+                    .find(|(_, pattern)| Self::parse(pattern).trimmed() == glyph.trimmed())
+                    .map(|(c, _)| *c)
+            })
+            .collect()
+    }
+
+    /// Renders with `#` and `.`, suitable for [`LitGrid::parse`]
+    pub fn to_text(&self) -> String {
+        (0..self.height())
+            .map(|y| {
+                (0..self.width())
+                    .map(|x| if self.is_lit(x, y) { '#' } else { '.' })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Convenience for [`LitGrid::recognise`] on `#`/`.` text
+pub fn recognise(text: &str) -> Option<String> {
+    LitGrid::parse(text).recognise()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(font: &[(char, &str)], word: &str, spacing: usize) -> String {
+        let glyphs = word
+            .chars()
+            .map(|c| {
+                let (_, pattern) = font.iter().find(|(fc, _)| *fc == c).unwrap();
+                LitGrid::parse(pattern)
+            })
+            .collect::<Vec<_>>();
+
+        let height = glyphs[0].height();
+        (0..height)
+            .map(|y| {
+                glyphs
+                    .iter()
+                    .map(|g| {
+                        let row = (0..g.width())
+                            .map(|x| if g.is_lit(x, y) { '#' } else { '.' })
+                            .collect::<String>();
+                        format!("{row}{}", ".".repeat(spacing))
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_small_font() {
+        let alphabet = SMALL_FONT.iter().map(|(c, _)| c).collect::<String>();
+        let text = render(SMALL_FONT, &alphabet, 1);
+        assert_eq!(recognise(&text).as_deref(), Some(alphabet.as_str()));
+    }
+
+    #[test]
+    fn test_large_font() {
+        let alphabet = LARGE_FONT.iter().map(|(c, _)| c).collect::<String>();
+        let text = render(LARGE_FONT, &alphabet, 2);
+        assert_eq!(recognise(&text).as_deref(), Some(alphabet.as_str()));
+    }
+
+    #[test]
+    fn test_padded_grid() {
+        let text = "\
+..........
+.#..#.###.
+.#..#..#..
+.####..#..
+.#..#..#..
+.#..#..#..
+.#..#.###.
+..........";
+        assert_eq!(recognise(text).as_deref(), Some("HI"));
+        assert_eq!(recognise("#.#\n.#.\n#.#"), None);
+    }
+}
@@ -0,0 +1,144 @@
+//! Progress of long-running searches, reported to the runner for display
+
+use std::ffi::c_void;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Minimum time between updates sent to the runner, so hot loops can report every iteration
+const REPORT_INTERVAL: Duration = Duration::from_millis(100);
+/// Calls to [`Progress::inc`] or [`Progress::set`] between looks at the clock, which would
+/// otherwise cost more than the loop being measured
+const CHECK_EVERY: u32 = 1024;
+
+#[repr(C)]
+pub struct ProgressUpdate {
+    pub current: u64,
+    /// 0 if unknown
+    pub total: u64,
+    pub message_ptr: *const u8,
+    pub message_len: usize,
+    pub finished: bool,
+}
+
+pub type ProgressSinkFn = unsafe extern "C" fn(ctx: *mut c_void, update: *const ProgressUpdate);
+
+/// Context pointer stored as an address so the static is `Send`
+static SINK: Mutex<Option<(ProgressSinkFn, usize)>> = Mutex::new(None);
+
+pub(crate) fn set_sink(sink: Option<(ProgressSinkFn, *mut c_void)>) {
+    *SINK.lock().unwrap() = sink.map(|(f, ctx)| (f, ctx as usize));
+}
+
+/// A progress bar shown by the runner. Finishes when dropped.
+pub struct Progress {
+    sink: Option<(ProgressSinkFn, usize)>,
+    total: Option<u64>,
+    current: u64,
+    message: String,
+    last_report: Instant,
+    /// Calls since the clock was last checked
+    unchecked: u32,
+}
+
+impl Progress {
+    pub fn new(total: Option<u64>, message: impl Into<String>) -> Self {
+        let mut progress = Self {
+            sink: *SINK.lock().unwrap(),
+            total,
+            current: 0,
+            message: message.into(),
+            last_report: Instant::now(),
+            unchecked: 0,
+        };
+
+        progress.report(false);
+        progress
+    }
+
+    pub fn inc(&mut self, n: u64) {
+        self.current += n;
+        self.tick();
+    }
+
+    pub fn set(&mut self, current: u64) {
+        self.current = current;
+        self.tick();
+    }
+
+    pub fn set_message(&mut self, message: impl Into<String>) {
+        self.message = message.into();
+        self.maybe_report();
+    }
+
+    fn tick(&mut self) {
+        self.unchecked += 1;
+        if self.unchecked >= CHECK_EVERY {
+            self.unchecked = 0;
+            self.maybe_report();
+        }
+    }
+
+    fn maybe_report(&mut self) {
+        if self.sink.is_some() && self.last_report.elapsed() >= REPORT_INTERVAL {
+            self.report(false);
+        }
+    }
+
+    fn report(&mut self, finished: bool) {
+        let Some((sink, ctx)) = self.sink else {
+            return;
+        };
+
+        let update = ProgressUpdate {
+            current: self.current,
+            total: self.total.unwrap_or(0),
+            message_ptr: self.message.as_ptr(),
+            message_len: self.message.len(),
+            finished,
+        };
+
+        unsafe { sink(ctx as *mut c_void, &update) };
+        self.last_report = Instant::now();
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        self.report(true);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    unsafe extern "C" fn count(ctx: *mut c_void, update: *const ProgressUpdate) {
+        let updates = &mut *(ctx as *mut Vec<(u64, bool)>);
+        updates.push(((*update).current, (*update).finished));
+    }
+
+    #[test]
+    fn test_throttling() {
+        let mut updates: Vec<(u64, bool)> = Vec::new();
+        let mut progress = Progress {
+            sink: Some((count, &mut updates as *mut _ as usize)),
+            total: None,
+            current: 0,
+            message: String::new(),
+            last_report: Instant::now() - REPORT_INTERVAL,
+            unchecked: 0,
+        };
+
+        for _ in 1..CHECK_EVERY {
+            progress.inc(1);
+        }
+        progress.set(5000);
+        // reported, so the interval starts again
+        for _ in 0..CHECK_EVERY {
+            progress.inc(1);
+        }
+        drop(progress);
+
+        assert_eq!(updates, [(5000, false), (5000 + CHECK_EVERY as u64, true)]);
+    }
+}
@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+use std::ffi::c_void;
+use std::sync::Mutex;
+
+/// How a case ended, for machine-readable output
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Outcome {
+    Pass,
+    /// Ran fine, but there is no expected answer to check against
+    Unchecked,
+    WrongAnswer,
+    /// Implementations gave different answers
+    Disagreement,
+    /// The solution returned an error, e.g. failing to parse its input
+    Error,
+    Panic,
+    Timeout,
+}
+
+/// One finished case, sent to the runner as JSON
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaseReport {
+    /// 1-based, as `--case` takes it
+    pub case: u32,
+    pub part: u8,
+    pub full_input: bool,
+    /// Only set when not running the default implementation alone
+    pub implementation: Option<String>,
+    pub outcome: Outcome,
+    pub answer: Option<String>,
+    pub expected: Option<String>,
+    /// Error or panic message, or what disagreed
+    pub message: Option<String>,
+    /// Of the first implementation run
+    pub elapsed_secs: Option<f64>,
+}
+
+pub type ReportSinkFn =
+    unsafe extern "C" fn(ctx: *mut c_void, report_ptr: *const u8, report_len: usize);
+
+/// Context pointer stored as an address so the static is `Send`
+static SINK: Mutex<Option<(ReportSinkFn, usize)>> = Mutex::new(None);
+
+pub(crate) fn set_sink(sink: Option<(ReportSinkFn, *mut c_void)>) {
+    *SINK.lock().unwrap() = sink.map(|(f, ctx)| (f, ctx as usize));
+}
+
+pub(crate) fn send(report: &CaseReport) {
+    let Some((sink, ctx)) = *SINK.lock().unwrap() else {
+        return;
+    };
+
+    let encoded = serde_json::to_vec(report).expect("reports always serialise");
+    unsafe { sink(ctx as *mut c_void, encoded.as_ptr(), encoded.len()) };
+}
+
+impl CaseReport {
+    pub fn decode(bytes: &[u8]) -> serde_json::Result<Self> {
+        serde_json::from_slice(bytes)
+    }
+}
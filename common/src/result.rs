@@ -0,0 +1,219 @@
+use crate::ocr::LitGrid;
+use num_bigint::{BigInt, BigUint};
+use std::fmt::Display;
+
+#[derive(Debug, Clone)]
+pub enum SolutionResult {
+    Int(i128),
+    UInt(u128),
+    BigInt(BigInt),
+    String(String),
+    /// Letters drawn in a grid, compared by their recognised text
+    Grid(LitGrid),
+}
+
+/// How a numeric result compares against a string one. Numbers always compare by value,
+/// regardless of width or signedness.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum MatchPolicy {
+    /// A string matches a number if it is exactly the number's decimal representation
+    #[default]
+    Canonical,
+    /// Numbers never match strings
+    Strict,
+}
+
+impl SolutionResult {
+    /// The value of a numeric result, or `None` for strings
+    pub fn to_bigint(&self) -> Option<BigInt> {
+        match self {
+            Self::Int(i) => Some(BigInt::from(*i)),
+            Self::UInt(u) => Some(BigInt::from(*u)),
+            Self::BigInt(b) => Some(b.clone()),
+            Self::String(_) | Self::Grid(_) => None,
+        }
+    }
+
+    pub fn is_numeric(&self) -> bool {
+        !matches!(self, Self::String(_) | Self::Grid(_))
+    }
+
+    /// Text a grid compares as: its recognised letters, or the picture itself if unrecognisable
+    fn grid_text(grid: &LitGrid) -> String {
+        grid.recognise().unwrap_or_else(|| grid.to_text())
+    }
+
+    /// How it's written as the answer of a case, e.g. `875318608908`. Numbers outside `i64`
+    /// go through `SolutionResult::from`, see [`CaseAnswer`]. Grids are written as the letters
+    /// they show.
+    pub fn literal(&self) -> String {
+        match self {
+            Self::Int(i) if i64::try_from(*i).is_ok() => i.to_string(),
+            Self::Int(i) => format!("SolutionResult::from({i}_i128)"),
+            Self::UInt(u) if i64::try_from(*u).is_ok() => u.to_string(),
+            Self::UInt(u) if u64::try_from(*u).is_ok() => format!("SolutionResult::from({u}_u64)"),
+            Self::UInt(u) => format!("SolutionResult::from({u}_u128)"),
+            other => format!("{:?}", other.compare_text()),
+        }
+    }
+
+    /// What it's compared as against a string
+    pub(crate) fn compare_text(&self) -> String {
+        match self {
+            Self::Grid(grid) => Self::grid_text(grid),
+            other => other.to_string(),
+        }
+    }
+
+    pub fn matches(&self, other: &Self, policy: MatchPolicy) -> bool {
+        match (self, other) {
+            (Self::Grid(a), Self::Grid(b)) => Self::grid_text(a) == Self::grid_text(b),
+            (Self::Grid(g), other) | (other, Self::Grid(g)) => {
+                Self::String(Self::grid_text(g)).matches(other, policy)
+            }
+            (Self::String(a), Self::String(b)) => a == b,
+            (Self::String(s), n) | (n, Self::String(s)) => match policy {
+                MatchPolicy::Canonical => *s == n.to_string(),
+                MatchPolicy::Strict => false,
+            },
+            (Self::Int(a), Self::Int(b)) => a == b,
+            (Self::UInt(a), Self::UInt(b)) => a == b,
+            (a, b) => a.to_bigint() == b.to_bigint(),
+        }
+    }
+}
+
+macro_rules! impl_from_int {
+    ($variant:ident, $wide:ty, $($int:ty),+) => {
+        $(
+            impl From<$int> for SolutionResult {
+                fn from(value: $int) -> Self {
+                    Self::$variant(<$wide>::from(value))
+                }
+            }
+        )+
+    };
+}
+
+impl_from_int!(Int, i128, i8, i16, i32, i64, i128);
+impl_from_int!(UInt, u128, u8, u16, u32, u64, u128);
+
+impl From<isize> for SolutionResult {
+    fn from(value: isize) -> Self {
+        Self::Int(value as i128)
+    }
+}
+
+impl From<usize> for SolutionResult {
+    fn from(value: usize) -> Self {
+        Self::UInt(value as u128)
+    }
+}
+
+impl From<BigInt> for SolutionResult {
+    fn from(value: BigInt) -> Self {
+        Self::BigInt(value)
+    }
+}
+
+impl From<BigUint> for SolutionResult {
+    fn from(value: BigUint) -> Self {
+        Self::BigInt(value.into())
+    }
+}
+
+impl From<LitGrid> for SolutionResult {
+    fn from(value: LitGrid) -> Self {
+        Self::Grid(value)
+    }
+}
+
+impl From<String> for SolutionResult {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+impl From<&str> for SolutionResult {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_owned())
+    }
+}
+
+/// What a case's answer is written as. `i64` is the only integer, so an unsuffixed literal like
+/// `107413700225434` is one rather than an overflowing `i32`; other widths are wrapped in
+/// `SolutionResult::from`.
+pub trait CaseAnswer: Into<SolutionResult> {}
+
+impl CaseAnswer for i64 {}
+impl CaseAnswer for BigInt {}
+impl CaseAnswer for LitGrid {}
+impl CaseAnswer for String {}
+impl CaseAnswer for &str {}
+impl CaseAnswer for SolutionResult {}
+
+/// Uses [`MatchPolicy::Canonical`], see [`SolutionResult::matches`] for others
+impl PartialEq<Self> for SolutionResult {
+    fn eq(&self, other: &Self) -> bool {
+        self.matches(other, MatchPolicy::Canonical)
+    }
+}
+
+impl Eq for SolutionResult {}
+
+impl Display for SolutionResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Int(i) => i.fmt(f),
+            Self::UInt(u) => u.fmt(f),
+            Self::BigInt(b) => b.fmt(f),
+            Self::String(s) => s.fmt(f),
+            Self::Grid(grid) => {
+                write!(
+                    f,
+                    "{}",
+                    grid.recognise().as_deref().unwrap_or("<unrecognised>")
+                )?;
+                for y in 0..grid.height() {
+                    writeln!(f)?;
+                    for x in 0..grid.width() {
+                        f.write_str(if grid.is_lit(x, y) { "█" } else { " " })?;
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[test]
+fn test_typed_comparison() {
+    let big = BigInt::from(u128::MAX) * 3_u32;
+
+    assert_eq!(SolutionResult::from(5_u8), SolutionResult::from(5_i64));
+    assert_eq!(SolutionResult::from(5_usize), SolutionResult::from("5"));
+    assert_ne!(
+        SolutionResult::from(-1_i32),
+        SolutionResult::from(u128::MAX)
+    );
+    assert_ne!(SolutionResult::from(5_i64), SolutionResult::from("05"));
+    assert_eq!(
+        SolutionResult::from(big.clone()),
+        SolutionResult::from(big.to_string())
+    );
+
+    assert!(!SolutionResult::from(5).matches(&"5".into(), MatchPolicy::Strict));
+    assert!(SolutionResult::from(u64::MAX).matches(&u64::MAX.into(), MatchPolicy::Strict));
+}
+
+#[test]
+fn test_grid_comparison() {
+    let hi = LitGrid::parse("#..#.###\n#..#..#.\n####..#.\n#..#..#.\n#..#..#.\n#..#.###");
+
+    assert_eq!(SolutionResult::from(hi.clone()), SolutionResult::from("HI"));
+    assert_eq!(SolutionResult::from(hi.clone()), SolutionResult::from(hi));
+    assert_ne!(
+        SolutionResult::from(LitGrid::parse("#")),
+        SolutionResult::from("I")
+    );
+}
@@ -0,0 +1,126 @@
+//! Solving one input outside of any case, for `aoc repl`
+
+use crate::report::{Outcome, ReportSinkFn};
+use crate::DEFAULT_IMPLEMENTATION;
+use crate::{panic_message, CaseError, PartNumber, Solution, SolutionResult, SolveOutput};
+use serde::{Deserialize, Serialize};
+use std::ffi::c_void;
+use std::time::Instant;
+
+/// Passed by pointer to `snippet_entrypoint`
+#[repr(C)]
+pub struct SnippetOptions {
+    pub input_ptr: *const u8,
+    pub input_len_bytes: usize,
+    pub part: u8,
+    /// Empty for the default implementation
+    pub impl_ptr: *const u8,
+    pub impl_len_bytes: usize,
+    pub report_sink: Option<ReportSinkFn>,
+    pub report_ctx: *mut c_void,
+}
+
+pub type SnippetEntrypointFn = unsafe extern "C" fn(options: *const SnippetOptions) -> bool;
+
+/// Bumped whenever [`SnippetOptions`] or [`SnippetReport`] change, as `aoc bench --compare`
+/// drives builds of other revisions through them
+pub const ABI_VERSION: u32 = 1;
+
+pub type AbiVersionFn = unsafe extern "C" fn() -> u32;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnippetReport {
+    /// [`Outcome::Unchecked`] if it gave an answer
+    pub outcome: Outcome,
+    pub answer: Option<String>,
+    /// Rust source for the answer, as `example_part1` takes it
+    pub answer_literal: Option<String>,
+    /// Error or panic message
+    pub message: Option<String>,
+    pub elapsed_secs: f64,
+}
+
+impl SnippetReport {
+    pub fn decode(bytes: &[u8]) -> serde_json::Result<Self> {
+        serde_json::from_slice(bytes)
+    }
+}
+
+/// Solves the input with `S::solve` or the named implementation, sending a [`SnippetReport`].
+/// Returns whether it gave an answer.
+///
+/// # Safety
+/// `options` must point to a valid [`SnippetOptions`].
+pub unsafe fn solve_snippet<S: Solution>(options: *const SnippetOptions) -> bool {
+    let options = &*options;
+    let str_from_raw = |ptr: *const u8, len: usize| {
+        std::str::from_utf8_unchecked(std::slice::from_raw_parts(ptr, len))
+    };
+    let input = S::INPUT_POLICY.apply(str_from_raw(options.input_ptr, options.input_len_bytes));
+    let input = &*input;
+    let name = str_from_raw(options.impl_ptr, options.impl_len_bytes);
+    let part = match options.part {
+        2 => PartNumber::Part2,
+        _ => PartNumber::Part1,
+    };
+
+    let implementations = S::implementations();
+    let implementation = match name {
+        "" | DEFAULT_IMPLEMENTATION => None,
+        name => implementations
+            .iter()
+            .find(|imp| imp.part == part && imp.name == name)
+            .map(Some)
+            .ok_or_else(|| format!("no part {} implementation named {name:?}", part as u8))
+            .transpose(),
+    };
+
+    let start = Instant::now();
+    let result = match implementation {
+        None => run(input, || {
+            S::solve(input, part)
+                .into_solve_result()
+                .map_err(CaseError::Parse)
+        }),
+        Some(Ok(imp)) => run(input, || imp.solve(input).map_err(CaseError::Parse)),
+        Some(Err(message)) => Err((Outcome::Error, message)),
+    };
+    let elapsed_secs = start.elapsed().as_secs_f64();
+
+    let report = match &result {
+        Ok(answer) => SnippetReport {
+            outcome: Outcome::Unchecked,
+            answer: Some(answer.to_string()),
+            answer_literal: Some(answer.literal()),
+            message: None,
+            elapsed_secs,
+        },
+        Err((outcome, message)) => SnippetReport {
+            outcome: *outcome,
+            answer: None,
+            answer_literal: None,
+            message: Some(message.clone()),
+            elapsed_secs,
+        },
+    };
+
+    if let Some(sink) = options.report_sink {
+        let encoded = serde_json::to_vec(&report).expect("reports always serialise");
+        sink(options.report_ctx, encoded.as_ptr(), encoded.len());
+    }
+    result.is_ok()
+}
+
+fn run(
+    input: &str,
+    solve: impl FnOnce() -> Result<SolutionResult, CaseError>,
+) -> Result<SolutionResult, (Outcome, String)> {
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(solve))
+        .map_err(|payload| CaseError::Panic(panic_message(&*payload)))
+        .and_then(|result| result);
+    result.map_err(|error| match error {
+        CaseError::Parse(e) => (Outcome::Error, e.report(input, "input")),
+        CaseError::Panic(message) => (Outcome::Panic, message),
+        CaseError::Timeout(timeout) => (Outcome::Timeout, format!("exceeded {timeout:?}")),
+    })
+}
@@ -0,0 +1,240 @@
+//! Frames recorded by solutions, for the runner to play back in the terminal or export
+
+use serde::{Deserialize, Serialize};
+use std::ffi::c_void;
+use std::sync::Mutex;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Self = Self(0, 0, 0);
+    pub const WHITE: Self = Self(255, 255, 255);
+    pub const GREY: Self = Self(128, 128, 128);
+    pub const RED: Self = Self(220, 50, 47);
+    pub const GREEN: Self = Self(80, 200, 80);
+    pub const YELLOW: Self = Self(230, 200, 40);
+    pub const BLUE: Self = Self(60, 120, 230);
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Cell {
+    pub c: char,
+    pub fg: Option<Rgb>,
+    pub bg: Option<Rgb>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CharFrame {
+    pub width: usize,
+    pub height: usize,
+    pub cells: Vec<Cell>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PixelFrame {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<Rgb>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Frame {
+    Chars(CharFrame),
+    Pixels(PixelFrame),
+}
+
+impl CharFrame {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![
+                Cell {
+                    c: ' ',
+                    fg: None,
+                    bg: None,
+                };
+                width * height
+            ],
+        }
+    }
+
+    /// One row per line, padded to the longest line
+    pub fn from_text(text: &str) -> Self {
+        let width = text.lines().map(|l| l.chars().count()).max().unwrap_or(0);
+        let mut frame = Self::new(width, text.lines().count());
+        for (y, line) in text.lines().enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                frame.set(x, y, c);
+            }
+        }
+
+        frame
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> &Cell {
+        &self.cells[y * self.width + x]
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> &mut Cell {
+        &mut self.cells[y * self.width + x]
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, c: char) {
+        self.get_mut(x, y).c = c;
+    }
+
+    pub fn set_fg(&mut self, x: usize, y: usize, fg: Rgb) {
+        self.get_mut(x, y).fg = Some(fg);
+    }
+
+    pub fn set_bg(&mut self, x: usize, y: usize, bg: Rgb) {
+        self.get_mut(x, y).bg = Some(bg);
+    }
+}
+
+impl PixelFrame {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![Rgb::BLACK; width * height],
+        }
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Rgb {
+        self.pixels[y * self.width + x]
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, colour: Rgb) {
+        self.pixels[y * self.width + x] = colour;
+    }
+}
+
+impl From<CharFrame> for Frame {
+    fn from(value: CharFrame) -> Self {
+        Self::Chars(value)
+    }
+}
+
+impl From<PixelFrame> for Frame {
+    fn from(value: PixelFrame) -> Self {
+        Self::Pixels(value)
+    }
+}
+
+impl Frame {
+    pub fn width(&self) -> usize {
+        match self {
+            Self::Chars(f) => f.width,
+            Self::Pixels(f) => f.width,
+        }
+    }
+
+    pub fn height(&self) -> usize {
+        match self {
+            Self::Chars(f) => f.height,
+            Self::Pixels(f) => f.height,
+        }
+    }
+
+    /// How frames are passed to the runner
+    pub fn encode(&self) -> Vec<u8> {
+        serde_json::to_vec(self).expect("frames always serialise")
+    }
+
+    /// Inverse of [`Frame::encode`]
+    pub fn decode(bytes: &[u8]) -> serde_json::Result<Self> {
+        serde_json::from_slice(bytes)
+    }
+}
+
+/// Receives each recorded frame, JSON encoded
+pub type FrameSinkFn =
+    unsafe extern "C" fn(ctx: *mut c_void, frame_ptr: *const u8, frame_len: usize);
+
+struct Sink {
+    f: FrameSinkFn,
+    /// Context pointer stored as an address so the static is `Send`
+    ctx: usize,
+    every: u32,
+    /// Frames offered so far, kept or not
+    seen: u32,
+}
+
+impl Sink {
+    fn keep(&mut self) -> bool {
+        let keep = self.seen.is_multiple_of(self.every);
+        self.seen = self.seen.wrapping_add(1);
+        keep
+    }
+}
+
+static SINK: Mutex<Option<Sink>> = Mutex::new(None);
+
+/// Keeps only every `every`th frame, so the rest are never built or encoded
+pub(crate) fn set_sink(sink: Option<(FrameSinkFn, *mut c_void)>, every: u32) {
+    *SINK.lock().unwrap() = sink.map(|(f, ctx)| Sink {
+        f,
+        ctx: ctx as usize,
+        every: every.max(1),
+        seen: 0,
+    });
+}
+
+/// Whether the runner asked for frames
+pub fn is_recording() -> bool {
+    SINK.lock().unwrap().is_some()
+}
+
+/// Records a frame if the runner asked for them. `frame` is only called for frames that are
+/// kept, so building it can be as slow as it likes.
+pub fn record<F: Into<Frame>>(frame: impl FnOnce() -> F) {
+    let (sink, ctx) = {
+        let mut guard = SINK.lock().unwrap();
+        let Some(sink) = guard.as_mut() else {
+            return;
+        };
+        if !sink.keep() {
+            return;
+        }
+        (sink.f, sink.ctx)
+    };
+
+    let encoded = frame().into().encode();
+    unsafe { sink(ctx as *mut c_void, encoded.as_ptr(), encoded.len()) };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let mut chars = CharFrame::from_text("#.\n.@");
+        chars.set_fg(1, 1, Rgb::RED);
+        chars.set_bg(0, 0, Rgb::BLUE);
+        let mut pixels = PixelFrame::new(2, 1);
+        pixels.set(1, 0, Rgb::GREEN);
+
+        for frame in [Frame::from(chars), Frame::from(pixels)] {
+            assert_eq!(Frame::decode(&frame.encode()).unwrap(), frame);
+        }
+    }
+
+    #[test]
+    fn test_every_nth() {
+        unsafe extern "C" fn ignore(_: *mut c_void, _: *const u8, _: usize) {}
+        let mut sink = Sink {
+            f: ignore,
+            ctx: 0,
+            every: 3,
+            seen: 0,
+        };
+
+        let kept = (0..10).filter(|_| sink.keep()).count();
+        assert_eq!(kept, 4);
+        assert_eq!(sink.seen, 10);
+    }
+}
@@ -0,0 +1,138 @@
+//! Running a solution built for `wasm32-wasip1` inside the runner's sandbox
+//!
+//! The host copies the input and a JSON [`WasmRunOptions`] into buffers from [`ALLOC_EXPORT`],
+//! then calls [`RUN_CASES_EXPORT`] as it would `run_cases_entrypoint`. Each case's report
+//! comes back through the [`REPORT_IMPORT`] host function, and printed output through WASI
+//! stdout.
+
+use crate::report::ReportSinkFn;
+use crate::{ImplSelection, PartNumber, RunConfig};
+use serde::{Deserialize, Serialize};
+use std::ffi::c_void;
+use std::time::Duration;
+
+/// Target solution crates are built for to run sandboxed
+pub const TARGET: &str = "wasm32-wasip1";
+/// `(len: u32) -> u32`, a buffer the host writes into
+pub const ALLOC_EXPORT: &str = "aoc_alloc";
+/// `(input_ptr, input_len, options_ptr, options_len: u32) -> i32`, whether every case passed
+pub const RUN_CASES_EXPORT: &str = "aoc_run_cases";
+pub const IMPORT_MODULE: &str = "aoc";
+/// `(report_ptr, report_len: u32)`, a JSON [`crate::report::CaseReport`]
+pub const REPORT_IMPORT: &str = "report";
+/// `(report_ptr, report_len: u32)`, the [`crate::report::CaseReport`] for the host to send if
+/// the case now starting times out
+pub const CASE_STARTED_IMPORT: &str = "case_started";
+
+/// [`crate::RunOptions`] without the pointers, which don't cross into wasm memory. There is no
+/// viz or progress, and the host enforces the timeout.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WasmRunOptions {
+    /// 0 for all parts
+    pub part_filter: u8,
+    /// 0 for all cases, otherwise 1-based
+    pub case_filter: u32,
+    pub solutions_only: bool,
+    /// Empty for the default implementation, see [`ImplSelection::parse`]
+    pub implementation: String,
+    pub bench: bool,
+    pub colour: bool,
+    pub input_changed: bool,
+    /// Per case, 0 for none
+    pub timeout_ms: u64,
+}
+
+impl WasmRunOptions {
+    pub fn run_config(&self, report_sink: Option<(ReportSinkFn, *mut c_void)>) -> RunConfig {
+        RunConfig {
+            part_filter: match self.part_filter {
+                0 => None,
+                1 => Some(PartNumber::Part1),
+                2 => Some(PartNumber::Part2),
+                n => panic!("invalid part number {n}"),
+            },
+            case_filter: self.case_filter,
+            solutions_only: self.solutions_only,
+            implementation: ImplSelection::parse(&self.implementation),
+            bench: self.bench,
+            viz_sink: None,
+            viz_every: 1,
+            progress_sink: None,
+            report_sink,
+            colour: self.colour,
+            timeout: (self.timeout_ms != 0).then(|| Duration::from_millis(self.timeout_ms)),
+            input_changed: self.input_changed,
+        }
+    }
+}
+
+/// Leaked, as the instance is thrown away after one run
+pub fn alloc(len: usize) -> *mut u8 {
+    Vec::<u8>::with_capacity(len).leak().as_mut_ptr()
+}
+
+/// The input and config from the buffers the host filled
+///
+/// # Safety
+/// Both buffers must be valid for their lengths, and the input UTF-8.
+#[cfg(target_family = "wasm")]
+pub unsafe fn from_raw<'a>(
+    input_ptr: *const u8,
+    input_len: usize,
+    options_ptr: *const u8,
+    options_len: usize,
+) -> (&'a str, RunConfig) {
+    let input = std::str::from_utf8_unchecked(std::slice::from_raw_parts(input_ptr, input_len));
+    let options: WasmRunOptions =
+        serde_json::from_slice(std::slice::from_raw_parts(options_ptr, options_len))
+            .expect("host sends valid options");
+    (
+        input,
+        options.run_config(Some((report_sink, std::ptr::null_mut()))),
+    )
+}
+
+#[cfg(target_family = "wasm")]
+unsafe extern "C" fn report_sink(_ctx: *mut c_void, report_ptr: *const u8, report_len: usize) {
+    #[link(wasm_import_module = "aoc")]
+    extern "C" {
+        fn report(report_ptr: *const u8, report_len: usize);
+    }
+    report(report_ptr, report_len);
+}
+
+/// There are no threads for a watchdog, so the host runs one, sending `report` if the case
+/// runs over
+#[cfg(target_family = "wasm")]
+pub(crate) fn case_started(report: &crate::report::CaseReport) {
+    #[link(wasm_import_module = "aoc")]
+    extern "C" {
+        fn case_started(report_ptr: *const u8, report_len: usize);
+    }
+    let encoded = serde_json::to_vec(report).expect("reports always serialise");
+    unsafe { case_started(encoded.as_ptr(), encoded.len()) };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_config() {
+        let options = WasmRunOptions {
+            part_filter: 2,
+            case_filter: 3,
+            implementation: "fast".to_owned(),
+            colour: true,
+            ..Default::default()
+        };
+        let json = serde_json::to_string(&options).unwrap();
+        let config = serde_json::from_str::<WasmRunOptions>(&json)
+            .unwrap()
+            .run_config(None);
+        assert_eq!(config.part_filter, Some(PartNumber::Part2));
+        assert_eq!(config.case_filter, 3);
+        assert!(matches!(config.implementation, ImplSelection::Named(name) if name == "fast"));
+        assert!(config.colour && config.timeout.is_none() && config.report_sink.is_none());
+    }
+}
@@ -24,9 +24,9 @@ day=$2
 [[ $year =~ ^20[0-9]{2}$ ]] || die "Invalid year: $year (must be 20XX)"
 (( year >= 2015 )) || die "Invalid year: $year (must be >= 2015)"
 
-# Validate day (1-25)
-[[ $day =~ ^[0-9]{1,2}$ ]] || die "Invalid day: $day (must be 1-25)"
-(( day >= 1 && day <= 25 )) || die "Invalid day: $day (must be 1-25)"
+# Validate day, which must be in the year's calendar and already unlocked
+[[ $day =~ ^[0-9]{1,2}$ ]] || die "Invalid day: $day"
+cargo run -q -p aoc -- calendar --year "$year" --day "$day" > /dev/null || die "No input to download yet"
 
 # Zero-pad day if needed
 day=$(printf "%02d" "$day")
@@ -34,7 +34,6 @@ day=$(printf "%02d" "$day")
 # Setup paths
 input_dir="inputs"
 input_file="${input_dir}/${year}-${day}"
-template_dir="solutions/aoc-YYYY-DD"
 target_dir="solutions/aoc-${year}-${day}"
 
 # Check if files already exist
@@ -50,25 +49,13 @@ if ! aoc download -y "$year" -d "$day" -I -i "$input_file" -q; then
     die "Failed to download input"
 fi
 
-# Copy and process solution template
+# Copy the solution template and link its source
 log_info "Setting up solution directory..."
-if ! cp -r "$template_dir" "$target_dir"; then
-    die "Failed to copy template directory"
+if ! cargo run -q -p aoc -- new --year "$year" --day "$day"; then
+    die "Failed to create solution"
 fi
 
-# Replace placeholders in Rust and TOML files
-log_info "Processing template files..."
-find "$target_dir" -type f \( -name "*.rs" -o -name "*.toml" \) -exec sed -i \
-    -e "s/YYYY/${year}/g" \
-    -e "s/DD/${day}/g" {} +
-
 log_info "Running git add..."
 git add "$target_dir"
 
-# Create symlink to lib.rs
-log_info "Creating solution symlink..."
-if ! ln -s "${target_dir}/src/lib.rs" "${year}-${day}.rs"; then
-    die "Failed to create solution symlink"
-fi
-
 log_info "Setup complete for AOC ${year} day ${day}"
\ No newline at end of file
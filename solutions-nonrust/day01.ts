@@ -1,4 +1,4 @@
-const input = await Bun.file("day01-input").text();
+const input = await Bun.stdin.text();
 
 
 // const l = [3, 4, 2, 1, 3, 3]
@@ -47,4 +47,5 @@ function partTwo() {
 
 }
 
-partTwo()
\ No newline at end of file
+if (Bun.argv[2] === "1") partOne();
+else partTwo();
@@ -1,4 +1,4 @@
-let input = await Bun.file("day02-input").text();
+const input = await Bun.stdin.text();
 
 class Report {
     constructor(line) {
@@ -46,18 +46,7 @@ class Report {
     }
 }
 
-let reports = [];
-
-// input = "7 6 4 2 1\n" +
-//     "1 2 7 8 9\n" +
-//     "9 7 6 2 1\n" +
-//     "1 3 2 4 5\n" +
-//     "8 6 4 4 1\n" +
-//     "1 3 6 7 9";
-
-reports = input.split("\n").map(line => new Report(line));
-
-console.log(reports);
+const reports = input.split("\n").map(line => new Report(line));
 
 
 function partOne() {
@@ -73,4 +62,5 @@ function partTwo() {
 }
 
 
-partTwo();
+if (Bun.argv[2] === "1") partOne();
+else partTwo();
@@ -1,7 +1,4 @@
-let input = await Bun.file("day03-input").text();
-
-let example = false;
-// example = true;
+const input = await Bun.stdin.text();
 
 // type State =
 //     | { char: "m" }
@@ -15,10 +12,6 @@ let example = false;
 //
 
 function partOne() {
-    if (example) {
-        input = `xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))`;
-    }
-
     const pattern = /mul\((\d{1,3}),(\d{1,3})\)/g;
     let sum = 0;
     for (const match of input.matchAll(pattern)) {
@@ -30,12 +23,6 @@ function partOne() {
 
 
 function partTwo() {
-
-    if (example) {
-        input = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))"
-    }
-
-
     const mulPattern = /mul\((\d{1,3}),(\d{1,3})\)/g;
     const doPattern = /do\(\)/g;
     const dontPattern = /don't\(\)/g;
@@ -78,4 +65,4 @@ function partTwo() {
 }
 
 
-console.log("result", partTwo());
\ No newline at end of file
+console.log(Bun.argv[2] === "1" ? partOne() : partTwo());
@@ -1,19 +1,4 @@
-let input = await Bun.file("day04-input").text();
-
-let part = 2;
-let example = true;
-example = false;
-
-let part1Example = `MMMSXXMASM
-MSAMXMSMSA
-AMXSXMAAMM
-MSAMASMSMX
-XMASAMXAMM
-XXAMMXXAMA
-SMSMSASXSS
-SAXAMASAAA
-MAMMMXMMMM
-MXMXAXMASX`;
+const input = await Bun.stdin.text();
 
 const offsetsIncludingDiagonals = [
     [0, 1],
@@ -69,9 +54,6 @@ class Grid {
 }
 
 function partOne() {
-    if (example)
-        input = part1Example;
-
     let grid = new Grid(input);
     let sum = 0;
     for (const offset of offsetsIncludingDiagonals) {
@@ -85,9 +67,6 @@ function partOne() {
 }
 
 function partTwo() {
-    if (example)
-        input = part1Example;
-
     let grid = new Grid(input);
     let sum = 0;
     let foundLocs = new Map();
@@ -105,4 +84,4 @@ function partTwo() {
     return sum;
 }
 
-console.log(part === 1 ? partOne() : partTwo());
+console.log(Bun.argv[2] === "1" ? partOne() : partTwo());
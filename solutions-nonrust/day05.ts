@@ -1,40 +1,4 @@
-const dayInput = await Bun.file("day05-input").text();
-
-let part = 2;
-let example = true;
-example = false;
-
-let part1Example = `47|53
-97|13
-97|61
-97|47
-75|29
-61|13
-75|53
-29|13
-97|29
-53|29
-61|53
-97|53
-61|29
-47|13
-75|47
-97|75
-47|61
-75|61
-47|29
-75|13
-53|13
-
-75,47,61,53,29
-97,61,53,29,13
-75,29,13
-75,97,47,61,53
-61,13,29
-97,13,75,29,47`;
-
-// leave blank if same as part1
-let part2Example = ``;
+const dayInput = await Bun.stdin.text();
 
 class Dependencies {
     public dependencies: Map<number, number[]>;
@@ -191,5 +155,5 @@ function partTwo(input: string) {
 }
 
 
-if (part === 1) console.log(partOne(example ? part1Example : dayInput));
-else console.log(partTwo(example ? (part2Example.length === 0 ? part1Example : part2Example) : dayInput));
+if (Bun.argv[2] === "1") console.log(partOne(dayInput));
+else console.log(partTwo(dayInput));
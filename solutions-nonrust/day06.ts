@@ -1,22 +1,4 @@
-const dayInput = await Bun.file("day06-input").text();
-
-let part = 2;
-let example = true;
-example = false;
-
-let part1Example = `....#.....
-.........#
-..........
-..#.......
-.......#..
-..........
-.#..^.....
-........#.
-#.........
-......#...`;
-
-// leave blank if same as part1
-let part2Example = ``;
+const dayInput = await Bun.stdin.text();
 
 enum Direction {
     Up,
@@ -194,5 +176,5 @@ function partTwo(input: string) {
 }
 
 
-if (part === 1) console.log(partOne(example ? part1Example : dayInput));
-else console.log(partTwo(example ? (part2Example.length === 0 ? part1Example : part2Example) : dayInput));
+if (Bun.argv[2] === "1") console.log(partOne(dayInput));
+else console.log(partTwo(dayInput));
@@ -3,7 +3,7 @@ use common::prelude::*;
 pub struct Day01_2015;
 
 impl Solution for Day01_2015 {
-    fn solve(input: &str, part: PartNumber) -> impl Into<SolutionResult> {
+    fn solve(input: &str, part: PartNumber) -> impl SolveOutput {
         match part {
             PartNumber::Part1 => find_destination_floor(input.trim()),
             PartNumber::Part2 => find_basement_pos(input.trim()),
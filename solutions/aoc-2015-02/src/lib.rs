@@ -3,7 +3,7 @@ use common::prelude::*;
 pub struct Day02_2015;
 
 impl Solution for Day02_2015 {
-    fn solve(input: &str, part: PartNumber) -> impl Into<SolutionResult> {
+    fn solve(input: &str, part: PartNumber) -> impl SolveOutput {
         match part {
             PartNumber::Part1 => input.lines().map(paper_requirement).sum::<i64>(),
             PartNumber::Part2 => input.lines().map(ribbon_requirement).sum::<i64>(),
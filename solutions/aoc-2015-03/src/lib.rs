@@ -5,7 +5,7 @@ use std::collections::HashSet;
 pub struct Day03_2015;
 
 impl Solution for Day03_2015 {
-    fn solve(input: &str, part: PartNumber) -> impl Into<SolutionResult> {
+    fn solve(input: &str, part: PartNumber) -> impl SolveOutput {
         match part {
             PartNumber::Part1 => visit_houses(input.trim()),
             PartNumber::Part2 => visit_houses_with_robo(input.trim()),
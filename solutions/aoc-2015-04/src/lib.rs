@@ -1,9 +1,10 @@
 use common::prelude::*;
+use common::progress::Progress;
 
 pub struct Day04_2015;
 
 impl Solution for Day04_2015 {
-    fn solve(input: &str, part: PartNumber) -> impl Into<SolutionResult> {
+    fn solve(input: &str, part: PartNumber) -> impl SolveOutput {
         match part {
             PartNumber::Part1 => find_lowest_number(input, 5),
             PartNumber::Part2 => find_lowest_number(input, 6),
@@ -33,7 +34,11 @@ fn hash(input: &str, i: i64) -> String {
 
 fn find_lowest_number(input: &str, leading_zeros: usize) -> i64 {
     let prefix = "0".repeat(leading_zeros);
+    let mut progress = Progress::new(None, format!("hashing for {leading_zeros} zeros"));
     (1..)
-        .find_map(|i| hash(input, i).starts_with(&prefix).then_some(i))
+        .find_map(|i| {
+            progress.inc(1);
+            hash(input, i).starts_with(&prefix).then_some(i)
+        })
         .unwrap()
 }
@@ -3,7 +3,7 @@ use common::prelude::*;
 pub struct Day05_2015;
 
 impl Solution for Day05_2015 {
-    fn solve(input: &str, part: PartNumber) -> impl Into<SolutionResult> {
+    fn solve(input: &str, part: PartNumber) -> impl SolveOutput {
         match part {
             PartNumber::Part1 => lines(input).filter(|line| is_nice(line)).count() as i64,
             PartNumber::Part2 => lines(input).filter(|line| is_nice_v2(line)).count() as i64,
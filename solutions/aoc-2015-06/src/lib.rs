@@ -5,7 +5,7 @@ use std::str::FromStr;
 pub struct Day06_2015;
 
 impl Solution for Day06_2015 {
-    fn solve(input: &str, part: PartNumber) -> impl Into<SolutionResult> {
+    fn solve(input: &str, part: PartNumber) -> impl SolveOutput {
         match part {
             PartNumber::Part1 => part_one(input),
             PartNumber::Part2 => part_two(input),
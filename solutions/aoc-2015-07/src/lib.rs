@@ -6,7 +6,7 @@ use std::collections::HashMap;
 pub struct Day07_2015;
 
 impl Solution for Day07_2015 {
-    fn solve(input: &str, part: PartNumber) -> impl Into<SolutionResult> {
+    fn solve(input: &str, part: PartNumber) -> impl SolveOutput {
         match part {
             PartNumber::Part1 => {
                 let mut state = State::default();
@@ -3,7 +3,7 @@ use common::prelude::*;
 pub struct Day08_2015;
 
 impl Solution for Day08_2015 {
-    fn solve(input: &str, part: PartNumber) -> impl Into<SolutionResult> {
+    fn solve(input: &str, part: PartNumber) -> impl SolveOutput {
         match part {
             PartNumber::Part1 => lines(input)
                 .map(|s| len(s) - len(&parse_string_literal(s)))
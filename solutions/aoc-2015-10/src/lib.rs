@@ -4,7 +4,7 @@ use std::iter::Iterator;
 pub struct Day10_2015;
 
 impl Solution for Day10_2015 {
-    fn solve(input: &str, part: PartNumber) -> impl Into<SolutionResult> {
+    fn solve(input: &str, part: PartNumber) -> impl SolveOutput {
         match part {
             PartNumber::Part1 => apply(input, 40),
             PartNumber::Part2 => apply(input, 50),
@@ -4,7 +4,7 @@ use serde_json::Value;
 pub struct Day12_2015;
 
 impl Solution for Day12_2015 {
-    fn solve(input: &str, part: PartNumber) -> impl Into<SolutionResult> {
+    fn solve(input: &str, part: PartNumber) -> impl SolveOutput {
         match part {
             PartNumber::Part1 => find_numbers(input, None),
             PartNumber::Part2 => find_numbers(input, Some("red")),
@@ -4,7 +4,7 @@ use std::collections::HashMap;
 pub struct Day13_2015;
 
 impl Solution for Day13_2015 {
-    fn solve(input: &str, part: PartNumber) -> impl Into<SolutionResult> {
+    fn solve(input: &str, part: PartNumber) -> impl SolveOutput {
         match part {
             PartNumber::Part1 => find_best_order(&parse_preferences(input)),
             PartNumber::Part2 => find_best_order(&parse_preferences(input).with_self_added()),
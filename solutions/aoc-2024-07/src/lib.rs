@@ -4,7 +4,7 @@ use common::prelude::*;
 pub struct Day07_2024;
 
 impl Solution for Day07_2024 {
-    fn solve(input: &str, part: PartNumber) -> impl Into<SolutionResult> {
+    fn solve(input: &str, part: PartNumber) -> impl SolveOutput {
         match part {
             PartNumber::Part1 => part_one(input),
             PartNumber::Part2 => part_two(input),
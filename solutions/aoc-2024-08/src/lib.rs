@@ -6,7 +6,7 @@ use std::str::FromStr;
 pub struct Day08_2024;
 
 impl Solution for Day08_2024 {
-    fn solve(input: &str, part: PartNumber) -> impl Into<SolutionResult> {
+    fn solve(input: &str, part: PartNumber) -> impl SolveOutput {
         match part {
             PartNumber::Part1 => part_one(input),
             PartNumber::Part2 => part_two(input),
@@ -46,7 +46,7 @@ struct Map {
 }
 
 impl FromStr for Map {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
         let mut cells_per_freq = HashMap::<_, Vec<_>>::new();
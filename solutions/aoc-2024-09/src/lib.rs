@@ -5,7 +5,7 @@ use std::iter::repeat_n;
 pub struct Day09_2024;
 
 impl Solution for Day09_2024 {
-    fn solve(input: &str, part: PartNumber) -> impl Into<SolutionResult> {
+    fn solve(input: &str, part: PartNumber) -> impl SolveOutput {
         assert_eq!(input.lines().count(), 1);
 
         match part {
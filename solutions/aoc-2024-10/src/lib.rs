@@ -5,7 +5,7 @@ use std::collections::HashMap;
 pub struct Day10_2024;
 
 impl Solution for Day10_2024 {
-    fn solve(input: &str, part: PartNumber) -> impl Into<SolutionResult> {
+    fn solve(input: &str, part: PartNumber) -> impl SolveOutput {
         match part {
             PartNumber::Part1 => {
                 let map = Map::new(input);
@@ -1,6 +1,8 @@
 #![allow(dead_code)]
 
+use crate::compress_ints_dupes::CompressedIntsDupes;
 use crate::compress_ints_zstd::CompressedIntsZstd;
+use crate::compressed_ints_small_separately::CompressedInts;
 
 use common::prelude::*;
 use hashbrown::hash_map::Entry;
@@ -10,12 +12,40 @@ use size_of::SizeOf;
 pub struct Day11_2024;
 
 impl Solution for Day11_2024 {
-    fn solve(input: &str, part: PartNumber) -> impl Into<SolutionResult> {
+    fn solve(input: &str, part: PartNumber) -> impl SolveOutput {
         match part {
             PartNumber::Part1 => count_recursively(input, 25),
             PartNumber::Part2 => count_recursively(input, 75),
         }
     }
+
+    // simulating 75 blinks one stone at a time doesn't fit in any of them, so part 1 only
+    fn implementations() -> Vec<Implementation> {
+        vec![
+            implementation(PartNumber::Part1, "zstd", |input| {
+                Stones::<CompressedIntsZstd>::new(input).blink_n_times(25)
+            }),
+            implementation(PartNumber::Part1, "small-separately", |input| {
+                Stones::<CompressedInts>::new(input).blink_n_times(25)
+            }),
+            implementation(PartNumber::Part1, "dupes", |input| {
+                Stones::<CompressedIntsDupes>::new(input).blink_n_times(25)
+            }),
+        ]
+    }
+
+    fn fuzz_targets() -> Vec<FuzzTarget> {
+        vec![fuzz_target(
+            PartNumber::Part1,
+            |gen| {
+                (0..gen.usize(1..=3))
+                    .map(|_| gen.int(0..=999_999).to_string())
+                    .join(" ")
+            },
+            |input| count_recursively(input, 25),
+            |input| Stones::<CompressedInts>::new(input).blink_n_times(25),
+        )]
+    }
 }
 
 solution!(
@@ -32,7 +62,17 @@ fn test_build_2024_11() {}
 
 // -----
 
-struct Stones(CompressedIntsZstd);
+struct Stones<L>(L);
+
+/// One of the compressed ways of storing the simulated stones
+trait StoneList: Default + FromIterator<u64> {
+    fn push(&mut self, stone: u64);
+    /// Called once every stone is pushed
+    fn finish(&mut self) {}
+    fn len(&self) -> usize;
+    fn stones(&self) -> impl Iterator<Item = u64> + '_;
+    fn print_stats(&self);
+}
 
 fn count_recursively(input: &str, max_depth: usize) -> i64 {
     input
@@ -81,21 +121,26 @@ fn iter_recursive(stone: u64, max_depth: usize) -> usize {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::Stones;
     use common::itertools::Either;
 
+    fn simulate<L: StoneList>(input: &str, blinks: usize) -> Vec<u64> {
+        let mut stones = Stones::<L>::new(input);
+        stones.blink_n_times(blinks);
+        stones.0.stones().collect_vec()
+    }
+
     #[track_caller]
     fn check(input: &str, blinks: usize, expected: Either<usize, Vec<u64>>) {
         let actual_len = count_recursively(input, blinks);
 
         if let Either::Right(expected) = expected.clone() {
-            let mut stones = Stones::new(input);
-            stones.blink_n_times(blinks);
-            assert_eq!(
-                stones.0.iter().collect_vec(),
-                expected,
-                "recursive len {actual_len}"
-            );
+            for actual in [
+                simulate::<CompressedIntsZstd>(input, blinks),
+                simulate::<CompressedInts>(input, blinks),
+                simulate::<CompressedIntsDupes>(input, blinks),
+            ] {
+                assert_eq!(actual, expected, "recursive len {actual_len}");
+            }
         }
 
         let expected_len = match expected {
@@ -164,7 +209,7 @@ mod compressed_ints_small_separately {
     use std::ops::{Deref, DerefMut};
 
     #[derive(Default, SizeOf)]
-    struct CompressedInts {
+    pub struct CompressedInts {
         small: Vec<u16>,
         big: Vec<u64>,
         indices_are_big: BitVecWrapped,
@@ -237,6 +282,24 @@ mod compressed_ints_small_separately {
         }
     }
 
+    impl StoneList for CompressedInts {
+        fn push(&mut self, stone: u64) {
+            self.push(stone)
+        }
+
+        fn len(&self) -> usize {
+            self.len()
+        }
+
+        fn stones(&self) -> impl Iterator<Item = u64> + '_ {
+            self.iter()
+        }
+
+        fn print_stats(&self) {
+            self.print_stats()
+        }
+    }
+
     impl FromIterator<u64> for CompressedInts {
         fn from_iter<T: IntoIterator<Item = u64>>(iter: T) -> Self {
             let mut ci = CompressedInts::default();
@@ -376,6 +439,28 @@ mod compress_ints_zstd {
         }
     }
 
+    impl StoneList for CompressedIntsZstd {
+        fn push(&mut self, stone: u64) {
+            self.push(stone)
+        }
+
+        fn finish(&mut self) {
+            self.ensure_ready()
+        }
+
+        fn len(&self) -> usize {
+            self.len()
+        }
+
+        fn stones(&self) -> impl Iterator<Item = u64> + '_ {
+            self.iter()
+        }
+
+        fn print_stats(&self) {
+            self.print_stats()
+        }
+    }
+
     impl PartialEq<Vec<u64>> for CompressedIntsZstd {
         fn eq(&self, other: &Vec<u64>) -> bool {
             self.iter().collect_vec() == *other
@@ -443,6 +528,24 @@ mod compress_ints_dupes {
         }
     }
 
+    impl StoneList for CompressedIntsDupes {
+        fn push(&mut self, stone: u64) {
+            self.push(stone)
+        }
+
+        fn len(&self) -> usize {
+            self.len()
+        }
+
+        fn stones(&self) -> impl Iterator<Item = u64> + '_ {
+            self.iter()
+        }
+
+        fn print_stats(&self) {
+            self.print_stats()
+        }
+    }
+
     impl PartialEq<Vec<u64>> for CompressedIntsDupes {
         fn eq(&self, other: &Vec<u64>) -> bool {
             self.iter().collect_vec() == *other
@@ -450,7 +553,7 @@ mod compress_ints_dupes {
     }
 }
 
-impl Stones {
+impl<L: StoneList> Stones<L> {
     fn new(input: &str) -> Self {
         assert_eq!(input.lines().count(), 1);
         Self(
@@ -462,10 +565,9 @@ impl Stones {
     }
 
     fn blink(&mut self) {
-        // let mut new_stones = CompressedIntsDupes::default();
-        let mut new_stones = CompressedIntsZstd::default();
+        let mut new_stones = L::default();
 
-        for stone in self.0.iter() {
+        for stone in self.0.stones() {
             if stone == 0 {
                 new_stones.push(1);
                 continue;
@@ -483,7 +585,7 @@ impl Stones {
             new_stones.push(stone.wrapping_mul(2024));
         }
 
-        new_stones.ensure_ready();
+        new_stones.finish();
         self.0 = new_stones;
     }
 
@@ -6,7 +6,7 @@ use std::collections::HashSet;
 pub struct Day12_2024;
 
 impl Solution for Day12_2024 {
-    fn solve(input: &str, part: PartNumber) -> impl Into<SolutionResult> {
+    fn solve(input: &str, part: PartNumber) -> impl SolveOutput {
         match part {
             PartNumber::Part1 => Grid::new(input).calculate_fence_price::<ModernPricing>(),
             PartNumber::Part2 => Grid::new(input).calculate_fence_price::<BulkPricing>(),
@@ -4,7 +4,7 @@ use regex::Regex;
 pub struct Day13_2024;
 
 impl Solution for Day13_2024 {
-    fn solve(input: &str, part: PartNumber) -> impl Into<SolutionResult> {
+    fn solve(input: &str, part: PartNumber) -> impl SolveOutput {
         match part {
             PartNumber::Part1 => ClawMachine::parse_lines(input)
                 .into_iter()
@@ -27,6 +27,34 @@ impl Solution for Day13_2024 {
                 .sum::<i64>(),
         }
     }
+
+    fn implementations() -> Vec<Implementation> {
+        vec![implementation(PartNumber::Part1, "smol-brain", |input| {
+            ClawMachine::parse_lines(input)
+                .into_iter()
+                .filter_map(|machine| machine.find_cost_to_win_smol_brain())
+                .sum::<i64>()
+        })]
+    }
+
+    fn fuzz_targets() -> Vec<FuzzTarget> {
+        vec![fuzz_target(
+            PartNumber::Part1,
+            ClawMachine::generate,
+            |input| {
+                ClawMachine::parse_lines(input)
+                    .into_iter()
+                    .filter_map(|machine| machine.find_cost_to_win_smol_brain())
+                    .sum::<i64>()
+            },
+            |input| {
+                ClawMachine::parse_lines(input)
+                    .into_iter()
+                    .filter_map(|machine| machine.find_cost_to_win_big_brain(Some(100)))
+                    .sum::<i64>()
+            },
+        )]
+    }
 }
 
 solution!(
@@ -92,6 +120,26 @@ impl ClawMachine {
             .collect()
     }
 
+    /// Mostly winnable machines within 100 presses, some with a prize anywhere
+    fn generate(gen: &mut Gen) -> String {
+        (0..gen.usize(1..=3))
+            .map(|_| {
+                let a = [gen.int(1..=99), gen.int(1..=99)];
+                let b = [gen.int(1..=99), gen.int(1..=99)];
+                let prize = if gen.bool() {
+                    [gen.int(0..=20000), gen.int(0..=20000)]
+                } else {
+                    let (presses_a, presses_b) = (gen.int(0..=100), gen.int(0..=100));
+                    [0, 1].map(|i| a[i] * presses_a + b[i] * presses_b)
+                };
+                format!(
+                    "Button A: X+{}, Y+{}\nButton B: X+{}, Y+{}\nPrize: X={}, Y={}",
+                    a[0], a[1], b[0], b[1], prize[0], prize[1]
+                )
+            })
+            .join("\n\n")
+    }
+
     fn with_added(mut self, add: i64) -> Self {
         self.prize[0] += add;
         self.prize[1] += add;
@@ -1,4 +1,5 @@
 use common::prelude::*;
+use common::viz::{self, PixelFrame, Rgb};
 use glam::{ivec2, uvec2, IVec2, UVec2};
 use std::fmt::{Display, Formatter};
 use std::str::FromStr;
@@ -6,7 +7,7 @@ use std::str::FromStr;
 pub struct Day14_2024;
 
 impl Solution for Day14_2024 {
-    fn solve(input: &str, part: PartNumber) -> impl Into<SolutionResult> {
+    fn solve(input: &str, part: PartNumber) -> impl SolveOutput {
         match part {
             PartNumber::Part1 => safety_after(input, 100),
             PartNumber::Part2 => RobotGrid::new(input).simulate_until_christmas_tree(),
@@ -48,7 +49,7 @@ struct Line {
 }
 
 impl FromStr for Line {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let ints = s
@@ -56,17 +57,22 @@ impl FromStr for Line {
             .map(|s| {
                 s.split("=")
                     .nth(1)
-                    .unwrap()
+                    .ok_or_else(|| ParseError::new(format!("expected `name=x,y`, got {s:?}")))?
                     .split(",")
-                    .map(|s| s.parse::<i32>().unwrap())
-                    .collect_vec()
+                    .map(|s| Ok(s.parse::<i32>()?))
+                    .collect::<ParseResult<Vec<_>>>()
             })
-            .collect_vec();
-
-        Ok(Line {
-            pos: ivec2(ints[0][0], ints[0][1]),
-            vel: ivec2(ints[1][0], ints[1][1]),
-        })
+            .collect::<ParseResult<Vec<_>>>()?;
+
+        match ints.as_slice() {
+            [pos, vel] if pos.len() == 2 && vel.len() == 2 => Ok(Line {
+                pos: ivec2(pos[0], pos[1]),
+                vel: ivec2(vel[0], vel[1]),
+            }),
+            _ => Err(ParseError::new(format!(
+                "expected `p=x,y v=x,y`, got {s:?}"
+            ))),
+        }
     }
 }
 
@@ -162,6 +168,14 @@ impl RobotGrid {
         (Printer(buf, self.dims).to_string(), interesting)
     }
 
+    fn to_frame(&self) -> PixelFrame {
+        let mut frame = PixelFrame::new(self.dims.x as usize, self.dims.y as usize);
+        for robot in &self.robots {
+            frame.set(robot.pos.x as usize, robot.pos.y as usize, Rgb::GREEN);
+        }
+        frame
+    }
+
     fn simulate(&mut self) {
         let dims = self.dims.as_ivec2();
         self.robots.iter_mut().for_each(|robot| {
@@ -184,6 +198,7 @@ impl RobotGrid {
 
             if interesting {
                 println!("{s}");
+                viz::record(|| self.to_frame());
                 return i;
             }
             self.simulate();
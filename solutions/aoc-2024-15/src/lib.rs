@@ -1,4 +1,5 @@
 use common::prelude::*;
+use common::viz::{self, CharFrame, Rgb};
 use glam::{ivec2, uvec2, IVec2, UVec2};
 use std::collections::HashSet;
 use std::iter::{Extend, Iterator};
@@ -6,40 +7,10 @@ use std::iter::{Extend, Iterator};
 pub struct Day15_2024;
 
 impl Solution for Day15_2024 {
-    fn solve(input: &str, part: PartNumber) -> impl Into<SolutionResult> {
+    fn solve(input: &str, part: PartNumber) -> impl SolveOutput {
         match part {
-            PartNumber::Part1 => {
-                let (mut world, insns) = World::parse(input);
-                world.print_grid();
-
-                for insn in insns {
-                    println!("move {insn:?}");
-                    world.move_robot(insn);
-                    world.print_grid();
-                }
-
-                world
-                    .iter_box_positions()
-                    .map(|p| (p.x + 100 * p.y) as i64)
-                    .sum::<i64>()
-            }
-
-            PartNumber::Part2 => {
-                let (mut world, insns) = World::parse(input);
-                world.make_wider();
-                world.print_grid();
-
-                for insn in insns {
-                    println!("move {insn:?}");
-                    world.move_robot(insn);
-                    world.print_grid();
-                }
-
-                world
-                    .iter_box_positions()
-                    .map(|p| (p.x + 100 * p.y) as i64)
-                    .sum::<i64>()
-            }
+            PartNumber::Part1 => simulate(input, false),
+            PartNumber::Part2 => simulate(input, true),
         }
     }
 }
@@ -107,6 +78,24 @@ fn test_build_2024_15() {}
 
 // -----
 
+fn simulate(input: &str, wider: bool) -> ParseResult<i64> {
+    let (mut world, insns) = World::parse(input)?;
+    if wider {
+        world.make_wider();
+    }
+    viz::record(|| world.to_frame());
+
+    for insn in insns {
+        world.move_robot(insn);
+        viz::record(|| world.to_frame());
+    }
+
+    Ok(world
+        .iter_box_positions()
+        .map(|p| (p.x + 100 * p.y) as i64)
+        .sum::<i64>())
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 enum Cell {
     Empty,
@@ -123,36 +112,45 @@ struct World {
 }
 
 impl World {
-    fn parse(s: &str) -> (Self, Vec<IVec2>) {
-        let (map, instructions) = s.split_once("\n\n").unwrap();
+    fn parse(s: &str) -> ParseResult<(Self, Vec<IVec2>)> {
+        let (map, instructions) = s
+            .split_once("\n\n")
+            .ok_or_else(|| ParseError::new("expected a blank line between map and moves"))?;
 
         let grid = map
-            .chars()
-            .filter(|c| *c != '\n')
-            .map(|c| match c {
-                '.' => Cell::Empty,
-                '#' => Cell::Wall,
-                '@' => Cell::Robot,
-                'O' => Cell::SingleBox,
-                _ => unreachable!("bad char {c}"),
+            .char_indices()
+            .filter(|(_, c)| *c != '\n')
+            .map(|(i, c)| match c {
+                '.' => Ok(Cell::Empty),
+                '#' => Ok(Cell::Wall),
+                '@' => Ok(Cell::Robot),
+                'O' => Ok(Cell::SingleBox),
+                _ => Err(ParseError::at(
+                    s,
+                    &map[i..i + c.len_utf8()],
+                    format!("bad map char {c:?}"),
+                )),
             })
-            .collect_vec();
+            .collect::<ParseResult<Vec<_>>>()?;
 
         let sz = (grid.len() as f32).sqrt() as usize;
         let dims = UVec2::splat(sz as u32);
 
         let insns = lines(instructions)
-            .flat_map(|l| l.chars())
-            .map(|c| match c {
-                '^' => ivec2(0, -1),
-                'v' => ivec2(0, 1),
-                '<' => ivec2(-1, 0),
-                '>' => ivec2(1, 0),
-                _ => unreachable!("bad char {c}"),
+            .flat_map(|l| {
+                l.char_indices()
+                    .map(move |(i, c)| (&l[i..i + c.len_utf8()], c))
             })
-            .collect_vec();
+            .map(|(at, c)| match c {
+                '^' => Ok(ivec2(0, -1)),
+                'v' => Ok(ivec2(0, 1)),
+                '<' => Ok(ivec2(-1, 0)),
+                '>' => Ok(ivec2(1, 0)),
+                _ => Err(ParseError::at(s, at, format!("bad move {c:?}"))),
+            })
+            .collect::<ParseResult<Vec<_>>>()?;
 
-        (World { grid, dims }, insns)
+        Ok((World { grid, dims }, insns))
     }
 
     fn make_wider(&mut self) {
@@ -173,25 +171,25 @@ impl World {
         self.grid = new_map;
     }
 
-    fn print_grid(&self) {
-        let mut s = String::new();
+    fn to_frame(&self) -> CharFrame {
+        let mut frame = CharFrame::new(self.dims.x as usize, self.dims.y as usize);
         for y in 0..self.dims.y {
             for x in 0..self.dims.x {
                 let cell = self.grid[(y * self.dims.x + x) as usize];
-                let c = match cell {
-                    Cell::Empty => '.',
-                    Cell::Wall => '#',
-                    Cell::Robot => '@',
-                    Cell::SingleBox => 'O',
-                    Cell::BoxLeft => '[',
-                    Cell::BoxRight => ']',
+                let (c, colour) = match cell {
+                    Cell::Empty => ('.', Rgb::GREY),
+                    Cell::Wall => ('#', Rgb::BLUE),
+                    Cell::Robot => ('@', Rgb::RED),
+                    Cell::SingleBox => ('O', Rgb::YELLOW),
+                    Cell::BoxLeft => ('[', Rgb::YELLOW),
+                    Cell::BoxRight => (']', Rgb::YELLOW),
                 };
-                s.push(c);
+                frame.set(x as usize, y as usize, c);
+                frame.set_fg(x as usize, y as usize, colour);
             }
-            s.push('\n');
         }
 
-        println!("{s}");
+        frame
     }
 
     fn move_robot(&mut self, dir: IVec2) {
@@ -7,7 +7,7 @@ use std::iter::{once, IntoIterator, Iterator};
 pub struct Day16_2024;
 
 impl Solution for Day16_2024 {
-    fn solve(input: &str, part: PartNumber) -> impl Into<SolutionResult> {
+    fn solve(input: &str, part: PartNumber) -> impl SolveOutput {
         match part {
             PartNumber::Part1 => solve_part1(input),
             PartNumber::Part2 => solve_part2(input),
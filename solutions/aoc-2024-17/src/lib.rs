@@ -5,10 +5,12 @@ use std::ops::BitXor;
 pub struct Day17_2024;
 
 impl Solution for Day17_2024 {
-    fn solve(input: &str, part: PartNumber) -> impl Into<SolutionResult> {
+    fn solve(input: &str, part: PartNumber) -> impl SolveOutput {
         match part {
-            PartNumber::Part1 => parse(input).compute_fully().into_iter().join(","),
-            PartNumber::Part2 => find_correct_a_register(input).to_string(),
+            PartNumber::Part1 => {
+                SolutionResult::from(parse(input).compute_fully().into_iter().join(","))
+            }
+            PartNumber::Part2 => SolutionResult::from(find_correct_a_register(input)),
         }
     }
 }
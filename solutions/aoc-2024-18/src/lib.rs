@@ -4,7 +4,7 @@ use glam::{ivec2, UVec2};
 pub struct Day18_2024;
 
 impl Solution for Day18_2024 {
-    fn solve(input: &str, part: PartNumber) -> impl Into<SolutionResult> {
+    fn solve(input: &str, part: PartNumber) -> impl SolveOutput {
         match part {
             PartNumber::Part1 => {
                 let mut space = parse_input(input);
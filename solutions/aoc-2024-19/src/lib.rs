@@ -5,7 +5,7 @@ use std::default::Default;
 pub struct Day19_2024;
 
 impl Solution for Day19_2024 {
-    fn solve(input: &str, part: PartNumber) -> impl Into<SolutionResult> {
+    fn solve(input: &str, part: PartNumber) -> impl SolveOutput {
         match part {
             PartNumber::Part1 => parse_input(input).iter_possible_designs().count() as i64,
             PartNumber::Part2 => parse_input(input).count_all_possible_designs() as i64,
@@ -6,7 +6,7 @@ use std::iter::empty;
 pub struct Day20_2024;
 
 impl Solution for Day20_2024 {
-    fn solve(input: &str, part: PartNumber) -> impl Into<SolutionResult> {
+    fn solve(input: &str, part: PartNumber) -> impl SolveOutput {
         match part {
             PartNumber::Part1 => {
                 let grid = parse_input(input);
@@ -9,7 +9,7 @@ use std::str::FromStr;
 pub struct Day21_2024;
 
 impl Solution for Day21_2024 {
-    fn solve(input: &str, part: PartNumber) -> impl Into<SolutionResult> {
+    fn solve(input: &str, part: PartNumber) -> impl SolveOutput {
         match part {
             PartNumber::Part1 => lines(input)
                 .map(|line| {
@@ -55,13 +55,16 @@ struct Code {
 }
 
 impl FromStr for Code {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        assert!(s.ends_with('A'));
+        let numeric = s
+            .strip_suffix('A')
+            .ok_or_else(|| ParseError::new(format!("code {s:?} doesn't end with A")))?;
+
         Ok(Self {
             chars: s.to_string(),
-            numeric: s[..s.len() - 1].parse().unwrap(),
+            numeric: numeric.parse()?,
         })
     }
 }
@@ -223,7 +226,9 @@ fn test_code() {
 #[test]
 fn test_len() {
     assert_eq!(
-        Day21_2024::solve("029A", PartNumber::Part1).into(),
-        (68i64 * 29).into()
+        Day21_2024::solve("029A", PartNumber::Part1)
+            .into_solve_result()
+            .unwrap(),
+        SolutionResult::from(68i64 * 29)
     );
 }
@@ -7,7 +7,7 @@ use std::sync::atomic::AtomicUsize;
 pub struct Day22_2024;
 
 impl Solution for Day22_2024 {
-    fn solve(input: &str, part: PartNumber) -> impl Into<SolutionResult> {
+    fn solve(input: &str, part: PartNumber) -> impl SolveOutput {
         match part {
             PartNumber::Part1 => lines(input)
                 .map(|line| {
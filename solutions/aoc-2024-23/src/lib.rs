@@ -6,20 +6,18 @@ use std::str::FromStr;
 pub struct Day23_2024;
 
 impl Solution for Day23_2024 {
-    fn solve(input: &str, part: PartNumber) -> impl Into<SolutionResult> {
+    fn solve(input: &str, part: PartNumber) -> impl SolveOutput {
         match part {
-            PartNumber::Part1 => input
-                .parse::<Connections>()
-                .unwrap()
-                .find_three_connections()
-                .filter(|computers| computers.iter().any(|c| c.starts_with('t')))
-                .count()
-                .to_string(),
+            PartNumber::Part1 => input.parse::<Connections>().map(|connections| {
+                connections
+                    .find_three_connections()
+                    .filter(|computers| computers.iter().any(|c| c.starts_with('t')))
+                    .count()
+                    .to_string()
+            }),
             PartNumber::Part2 => input
                 .parse::<Connections>()
-                .unwrap()
-                .find_longest_chain()
-                .join(","),
+                .map(|connections| connections.find_longest_chain().join(",")),
         }
     }
 }
@@ -77,19 +75,21 @@ struct Connections {
 }
 
 impl FromStr for Connections {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let connections = lines(s)
             .map(|line| {
-                let (a, b) = line.split_once('-').unwrap();
-                if a < b {
+                let (a, b) = line
+                    .split_once('-')
+                    .ok_or_else(|| ParseError::at(s, line, "expected `a-b`"))?;
+                Ok(if a < b {
                     (a.to_string(), b.to_string())
                 } else {
                     (b.to_string(), a.to_string())
-                }
+                })
             })
-            .collect::<HashSet<_>>();
+            .collect::<ParseResult<HashSet<_>>>()?;
 
         let adj = connections
             .iter()
@@ -1,12 +1,13 @@
 use common::prelude::*;
+use common::progress::Progress;
 use std::collections::HashMap;
 
 pub struct Day24_2024;
 
 impl Solution for Day24_2024 {
-    fn solve(input: &str, part: PartNumber) -> impl Into<SolutionResult> {
+    fn solve(input: &str, part: PartNumber) -> impl SolveOutput {
         match part {
-            PartNumber::Part1 => parse(input).evaluate().to_string(),
+            PartNumber::Part1 => parse(input).map(|state| state.evaluate().to_string()),
             PartNumber::Part2 => solve_part2(input),
         }
     }
@@ -199,45 +200,48 @@ impl InitialState {
     }
 }
 
-fn parse(input: &str) -> InitialState {
+fn parse(input: &str) -> ParseResult<InitialState> {
     let mut lines = input.lines();
     let values = (&mut lines)
         .take_while(|l| !l.is_empty())
-        .map(|l| l.split_once(": ").unwrap())
-        .map(|(name, val)| {
-            (
-                name.trim().to_string(),
-                match val.trim() {
-                    "0" => false,
-                    "1" => true,
-                    _ => unreachable!("bad value {val}"),
-                },
-            )
+        .map(|l| {
+            let (name, val) = l
+                .split_once(": ")
+                .ok_or_else(|| ParseError::at(input, l, "expected `name: value`"))?;
+            let val = match val.trim() {
+                "0" => false,
+                "1" => true,
+                _ => return Err(ParseError::at(input, val, format!("bad value {val:?}"))),
+            };
+
+            Ok((name.trim().to_string(), val))
         })
-        .collect::<HashMap<_, _>>();
+        .collect::<ParseResult<HashMap<_, _>>>()?;
 
     let gates = lines
         .map(|l| {
-            let (lhs, rhs) = l.split_once(" -> ").unwrap();
+            let malformed = || ParseError::at(input, l, "expected `a OP b -> out`");
+            let (lhs, rhs) = l.split_once(" -> ").ok_or_else(malformed)?;
             let mut words = lhs.split_whitespace();
-            let a = words.next().unwrap();
-            let operation = match words.next().unwrap() {
+            let a = words.next().ok_or_else(malformed)?;
+            let op = words.next().ok_or_else(malformed)?;
+            let operation = match op {
                 "AND" => Operation::And,
                 "OR" => Operation::Or,
                 "XOR" => Operation::Xor,
-                _ => unreachable!("bad operand {words:?}"),
+                _ => return Err(ParseError::at(input, op, format!("bad operation {op:?}"))),
             };
-            let b = words.next().unwrap();
+            let b = words.next().ok_or_else(malformed)?;
 
-            (rhs.to_owned(), (a.to_string(), operation, b.to_string()))
+            Ok((rhs.to_owned(), (a.to_string(), operation, b.to_string())))
         })
-        .collect::<HashMap<_, _>>();
+        .collect::<ParseResult<HashMap<_, _>>>()?;
 
-    InitialState { values, gates }
+    Ok(InitialState { values, gates })
 }
 
-fn solve_part2(input: &str) -> String {
-    let state = parse(input);
+fn solve_part2(input: &str) -> ParseResult<String> {
+    let state = parse(input)?;
     assert!(!state.is_correct());
 
     let mut bad_gates = vec![];
@@ -293,12 +297,17 @@ fn solve_part2(input: &str) -> String {
         .permutations(8)
         .map(|p| [(p[0], p[1]), (p[2], p[3]), (p[4], p[5]), (p[6], p[7])]);
 
+    let n = bad_gates.len() as u64;
+    let total = (n.saturating_sub(7)..=n).product();
+    let mut progress = Progress::new(Some(total), "trying swaps");
+
     for swap in swaps {
+        progress.inc(1);
         let mut state = state.clone();
         for pair in swap.iter() {
             state.swap(pair.0, pair.1);
             if state.is_correct() {
-                return swap.iter().flat_map(|(a, b)| [a, b]).sorted().join(",");
+                return Ok(swap.iter().flat_map(|(a, b)| [a, b]).sorted().join(","));
             }
         }
     }
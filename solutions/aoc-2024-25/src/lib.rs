@@ -3,7 +3,7 @@ use common::prelude::*;
 pub struct Day25_2024;
 
 impl Solution for Day25_2024 {
-    fn solve(input: &str, part: PartNumber) -> impl Into<SolutionResult> {
+    fn solve(input: &str, part: PartNumber) -> impl SolveOutput {
         match part {
             PartNumber::Part1 => solve(input),
             PartNumber::Part2 => -1_i64,
@@ -3,7 +3,7 @@ use common::prelude::*;
 pub struct Day01_2025;
 
 impl Solution for Day01_2025 {
-    fn solve(input: &str, part: PartNumber) -> impl Into<SolutionResult> {
+    fn solve(input: &str, part: PartNumber) -> impl SolveOutput {
         match part {
             PartNumber::Part1 => turn(50, input).1 as i64,
             PartNumber::Part2 => {
@@ -4,7 +4,7 @@ use std::collections::HashSet;
 pub struct Day02_2025;
 
 impl Solution for Day02_2025 {
-    fn solve(input: &str, part: PartNumber) -> impl Into<SolutionResult> {
+    fn solve(input: &str, part: PartNumber) -> impl SolveOutput {
         match part {
             PartNumber::Part1 => find_invalids_part1(input).into_iter().sum::<u64>() as i64,
             PartNumber::Part2 => find_invalids_part2(input).into_iter().sum::<u64>() as i64,
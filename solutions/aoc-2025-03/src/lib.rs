@@ -4,7 +4,7 @@ use std::cmp::Reverse;
 pub struct Day03_2025;
 
 impl Solution for Day03_2025 {
-    fn solve(input: &str, part: PartNumber) -> impl Into<SolutionResult> {
+    fn solve(input: &str, part: PartNumber) -> impl SolveOutput {
         match part {
             PartNumber::Part1 => solve(input, 2),
             PartNumber::Part2 => solve(input, 12),
@@ -24,7 +24,7 @@ solution!(
         ),
         solution_part1(Some(17535)),
         example_part2(
-            3121910778619,
+            3121910778619_i64,
             "987654321111111
 811111111111119
 234234234234278
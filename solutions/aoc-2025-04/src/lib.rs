@@ -1,11 +1,12 @@
 use common::prelude::*;
+use common::viz::{self, CharFrame, Rgb};
 use glam::{ivec2, uvec2, IVec2, UVec2};
 use std::collections::HashSet;
 
 pub struct Day04_2025;
 
 impl Solution for Day04_2025 {
-    fn solve(input: &str, part: PartNumber) -> impl Into<SolutionResult> {
+    fn solve(input: &str, part: PartNumber) -> impl SolveOutput {
         match part {
             PartNumber::Part1 => solve_part1(input) as i64,
             PartNumber::Part2 => solve_part2(input) as i64,
@@ -94,25 +95,26 @@ impl Grid {
         }
     }
 
-    fn print(&self, extras: &HashSet<UVec2>) {
+    fn to_frame(&self, extras: &HashSet<UVec2>) -> CharFrame {
+        let mut frame = CharFrame::new(self.dims.x as usize, self.dims.y as usize);
         for y in 0..self.dims.y {
             for x in 0..self.dims.x {
                 let idx = (y * self.dims.x + x) as usize;
-                let c = if extras.contains(&uvec2(x, y)) {
-                    'x'
+                let (c, colour) = if extras.contains(&uvec2(x, y)) {
+                    ('x', Rgb::RED)
                 } else if self.removed[idx] {
-                    ','
+                    (',', Rgb::GREY)
+                } else if self.cells[idx] {
+                    ('@', Rgb::WHITE)
                 } else {
-                    if self.cells[idx] {
-                        '@'
-                    } else {
-                        '.'
-                    }
+                    ('.', Rgb::GREY)
                 };
-                print!("{c}");
+                frame.set(x as usize, y as usize, c);
+                frame.set_fg(x as usize, y as usize, colour);
             }
-            println!();
         }
+
+        frame
     }
 
     fn find_accessible(&self) -> Vec<UVec2> {
@@ -165,7 +167,7 @@ impl Grid {
 fn solve_part1(input: &str) -> usize {
     let grid = Grid::new(input);
     let accessible = grid.find_accessible();
-    grid.print(&accessible.iter().copied().collect());
+    viz::record(|| grid.to_frame(&accessible.iter().copied().collect()));
     accessible.len()
 }
 fn solve_part2(input: &str) -> usize {
@@ -175,7 +177,7 @@ fn solve_part2(input: &str) -> usize {
 
     loop {
         let accessible = grid.find_accessible();
-        grid.print(&accessible.iter().copied().collect());
+        viz::record(|| grid.to_frame(&accessible.iter().copied().collect()));
         println!("remove {}", accessible.len());
 
         if accessible.is_empty() {
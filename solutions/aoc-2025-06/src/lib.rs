@@ -4,7 +4,10 @@ use std::iter::once;
 pub struct Day06_2025;
 
 impl Solution for Day06_2025 {
-    fn solve(input: &str, part: PartNumber) -> impl Into<SolutionResult> {
+    // the worksheet's columns are aligned with spaces, including at the end of lines
+    const INPUT_POLICY: InputPolicy = InputPolicy::StripTrailingBlankLines;
+
+    fn solve(input: &str, part: PartNumber) -> impl SolveOutput {
         match part {
             PartNumber::Part1 => solve(input) as i64,
             PartNumber::Part2 => solve_char_grid(input) as i64,
@@ -4,7 +4,7 @@ use std::collections::HashMap;
 pub struct Day07_2025;
 
 impl Solution for Day07_2025 {
-    fn solve(input: &str, part: PartNumber) -> impl Into<SolutionResult> {
+    fn solve(input: &str, part: PartNumber) -> impl SolveOutput {
         match part {
             PartNumber::Part1 => solve_part1(input) as i64,
             PartNumber::Part2 => solve_part2(input) as i64,
@@ -6,10 +6,10 @@ use std::collections::HashMap;
 pub struct Day08_2025;
 
 impl Solution for Day08_2025 {
-    fn solve(input: &str, part: PartNumber) -> impl Into<SolutionResult> {
+    fn solve(input: &str, part: PartNumber) -> impl SolveOutput {
         match part {
-            PartNumber::Part1 => solve(input) as i64,
-            PartNumber::Part2 => solve_part2(input) as i64,
+            PartNumber::Part1 => SolutionResult::from(solve(input)),
+            PartNumber::Part2 => SolutionResult::from(solve_part2(input)),
         }
     }
 }
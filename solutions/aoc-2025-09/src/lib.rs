@@ -5,7 +5,7 @@ use glam::{uvec2, UVec2};
 pub struct Day09_2025;
 
 impl Solution for Day09_2025 {
-    fn solve(input: &str, part: PartNumber) -> impl Into<SolutionResult> {
+    fn solve(input: &str, part: PartNumber) -> impl SolveOutput {
         match part {
             PartNumber::Part1 => solve(input),
             PartNumber::Part2 => solve_part2(input),
@@ -1,4 +1,5 @@
 use common::prelude::*;
+use common::progress::Progress;
 use good_lp::{
     default_solver, variable, Expression, ProblemVariables, Solution as Gtfo, SolverModel,
 };
@@ -7,12 +8,20 @@ use smallvec::{smallvec, SmallVec};
 pub struct Day10_2025;
 
 impl Solution for Day10_2025 {
-    fn solve(input: &str, part: PartNumber) -> impl Into<SolutionResult> {
+    fn solve(input: &str, part: PartNumber) -> impl SolveOutput {
         match part {
             PartNumber::Part1 => solve(input),
             PartNumber::Part2 => solve_part2(input),
         }
     }
+
+    fn implementations() -> Vec<Implementation> {
+        vec![implementation(
+            PartNumber::Part2,
+            "astar",
+            solve_part2_astar,
+        )]
+    }
 }
 
 solution!(
@@ -59,7 +68,7 @@ struct Button {
     toggles: Vec<u8>,
 }
 
-fn parse(input: &str) -> Vec<Machine> {
+fn parse(input: &str) -> ParseResult<Vec<Machine>> {
     lines(input)
         .map(|s| {
             let mut buttons = vec![];
@@ -68,12 +77,18 @@ fn parse(input: &str) -> Vec<Machine> {
             for chunk in s.split_whitespace() {
                 match chunk.chars().next().unwrap() {
                     '[' => {
-                        for c in chunk.chars().skip(1) {
+                        for (i, c) in chunk.char_indices().skip(1) {
                             let on = match c {
                                 '.' => false,
                                 '#' => true,
                                 ']' => break,
-                                _ => unreachable!(),
+                                _ => {
+                                    return Err(ParseError::at(
+                                        input,
+                                        &chunk[i..i + c.len_utf8()],
+                                        format!("bad light {c:?}"),
+                                    ))
+                                }
                             };
 
                             lights.push(on);
@@ -83,11 +98,10 @@ fn parse(input: &str) -> Vec<Machine> {
                     '(' => {
                         let mut button_lights = Vec::new();
                         for num in chunk.split(',') {
-                            let num = num
-                                .trim_matches(|c| c == '(' || c == ')')
-                                .parse::<u8>()
-                                .unwrap();
-                            button_lights.push(num);
+                            let num = num.trim_matches(|c| c == '(' || c == ')');
+                            button_lights.push(num.parse::<u8>().map_err(|e| {
+                                ParseError::at(input, num, format!("bad button index: {e}"))
+                            })?);
                         }
 
                         buttons.push(Button {
@@ -96,29 +110,35 @@ fn parse(input: &str) -> Vec<Machine> {
                     }
 
                     '{' => {
-                        assert!(joltage_counters.is_empty());
+                        if !joltage_counters.is_empty() {
+                            return Err(ParseError::at(input, chunk, "duplicate joltage counters"));
+                        }
                         for num in chunk.split(',') {
-                            let num = num
-                                .trim_matches(|c| c == '{' || c == '}')
-                                .parse::<u32>()
-                                .unwrap();
-                            joltage_counters.push(num);
+                            let num = num.trim_matches(|c| c == '{' || c == '}');
+                            joltage_counters.push(num.parse::<u32>().map_err(|e| {
+                                ParseError::at(input, num, format!("bad joltage counter: {e}"))
+                            })?);
                         }
                     }
-                    _ => unreachable!(),
+                    _ => return Err(ParseError::at(input, chunk, "unexpected chunk")),
                 }
             }
 
-            assert!(!buttons.is_empty());
-            assert!(!lights.is_empty());
-            assert!(!joltage_counters.is_empty());
-            Machine {
+            if buttons.is_empty() || lights.is_empty() || joltage_counters.is_empty() {
+                return Err(ParseError::at(
+                    input,
+                    s,
+                    "expected lights, buttons and joltage counters",
+                ));
+            }
+
+            Ok(Machine {
                 goal_lights: Lights(lights),
                 buttons,
                 goal_counters: joltage_counters,
-            }
+            })
         })
-        .collect_vec()
+        .collect()
 }
 
 impl Machine {
@@ -185,8 +205,10 @@ impl Machine {
         }
         assert_eq!(actual_counters, self.goal_counters, "wrong!");
 
-        return sum;
+        sum
+    }
 
+    fn solve_part2_astar(&self) -> usize {
         let buttons = self.buttons.clone();
         let start: SmallVec<u32, 16> = smallvec![0u32; self.goal_counters.len()];
         let goal = self
@@ -195,7 +217,7 @@ impl Machine {
             .into_iter()
             .collect::<SmallVec<_, 16>>();
 
-        let mut n = 0usize;
+        let mut progress = Progress::new(None, "exploring states");
         pathfinding::directed::astar::astar(
             &start,
             |state| {
@@ -206,10 +228,7 @@ impl Machine {
                 // }
                 //
                 let state = state.clone();
-                n += 1;
-                if n % 5000_000 == 0 {
-                    println!("explored {n} states, state is {state:?}, goal is {goal:?}",);
-                }
+                progress.inc(1);
                 let vec = buttons
                     .iter()
                     .filter_map(move |button| {
@@ -242,13 +261,21 @@ impl Machine {
     }
 }
 
-fn solve(input: &str) -> i64 {
-    let machines = parse(input);
-    machines.iter().map(|m| m.solve()).sum::<usize>() as i64
+fn solve(input: &str) -> ParseResult<i64> {
+    let machines = parse(input)?;
+    Ok(machines.iter().map(|m| m.solve()).sum::<usize>() as i64)
 }
 
-fn solve_part2(input: &str) -> i64 {
-    let machines = parse(input);
+fn solve_part2(input: &str) -> ParseResult<i64> {
+    let machines = parse(input)?;
     println!("parsed {} machines", machines.len());
-    machines.iter().map(|m| m.solve_part2()).sum::<usize>() as i64
+    Ok(machines.iter().map(|m| m.solve_part2()).sum::<usize>() as i64)
+}
+
+fn solve_part2_astar(input: &str) -> ParseResult<i64> {
+    let machines = parse(input)?;
+    Ok(machines
+        .iter()
+        .map(|m| m.solve_part2_astar())
+        .sum::<usize>() as i64)
 }
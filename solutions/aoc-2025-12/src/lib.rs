@@ -6,7 +6,7 @@ use std::iter::repeat_n;
 pub struct Day12_2025;
 
 impl Solution for Day12_2025 {
-    fn solve(input: &str, part: PartNumber) -> impl Into<SolutionResult> {
+    fn solve(input: &str, part: PartNumber) -> impl SolveOutput {
         match part {
             PartNumber::Part1 => solve(input) as i64,
             PartNumber::Part2 => -1_i64,
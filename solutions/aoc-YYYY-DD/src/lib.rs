@@ -3,7 +3,7 @@ use common::prelude::*;
 pub struct DayDD_YYYY;
 
 impl Solution for DayDD_YYYY {
-    fn solve(input: &str, part: PartNumber) -> impl Into<SolutionResult> {
+    fn solve(input: &str, part: PartNumber) -> impl SolveOutput {
         match part {
             PartNumber::Part1 => -1_i64,
             PartNumber::Part2 => -1_i64,